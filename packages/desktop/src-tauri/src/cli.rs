@@ -0,0 +1,241 @@
+//! `yep-anywhere-desktop status|start|stop|restart|logs|url`.
+//!
+//! The single-instance plugin hands a second launch's argv to the running
+//! instance and then exits the second process, so it can't carry a reply.
+//! The CLI therefore re-runs itself with `--reply-to <file>`: that child is
+//! what gets forwarded, and the running instance writes the result to the
+//! file, which the original process prints.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::{config, logs, server};
+
+const REPLY_FLAG: &str = "--reply-to";
+/// How long to wait for the running instance to answer. Generous, since
+/// `stop` and `restart` wait for the server to drain.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(120);
+const REPLY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const DEFAULT_LOG_LINES: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Status,
+    Start,
+    Stop,
+    Restart,
+    Logs { lines: usize },
+    Url,
+}
+
+/// What the running instance writes back to the reply file.
+#[derive(Debug, Serialize, Deserialize)]
+struct Reply {
+    ok: bool,
+    output: String,
+}
+
+/// The subcommand in `argv` (including the program name), if any.
+pub fn parse(argv: &[String]) -> Option<Result<Command, String>> {
+    // Positional arguments only: skip flags such as `--headless` (and macOS's
+    // `-psn_…`), and the value of `--reply-to`.
+    let mut positional = vec![];
+    let mut args = argv.iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == REPLY_FLAG {
+            args.next();
+        } else if !arg.starts_with('-') {
+            positional.push(arg.as_str());
+        }
+    }
+    let mut args = positional.into_iter();
+
+    let command = match args.next()? {
+        "status" => Command::Status,
+        "start" => Command::Start,
+        "stop" => Command::Stop,
+        "restart" => Command::Restart,
+        "logs" => match args.next() {
+            Some(n) => match n.parse() {
+                Ok(lines) => Command::Logs { lines },
+                Err(_) => return Some(Err(format!("Invalid line count: {n}"))),
+            },
+            None => Command::Logs {
+                lines: DEFAULT_LOG_LINES,
+            },
+        },
+        "url" => Command::Url,
+        other => return Some(Err(format!("Unknown command: {other}"))),
+    };
+    Some(Ok(command))
+}
+
+/// The `--reply-to` file, present when this process was launched by the CLI
+/// to be forwarded to the running instance.
+pub fn reply_path(argv: &[String]) -> Option<PathBuf> {
+    argv.iter()
+        .position(|arg| arg == REPLY_FLAG)
+        .and_then(|i| argv.get(i + 1))
+        .map(PathBuf::from)
+}
+
+/// Handle a CLI invocation from `main`, before any Tauri setup. Returns the
+/// exit code if this process was a CLI client, or `None` to start the app.
+pub fn dispatch() -> Option<i32> {
+    let argv: Vec<String> = std::env::args().collect();
+    if reply_path(&argv).is_some() {
+        // We are the forwarded child; let the single-instance plugin run.
+        return None;
+    }
+    match parse(&argv)? {
+        Ok(_) => Some(forward(&argv)),
+        Err(e) => {
+            eprintln!("{e}");
+            eprintln!("Usage: yep-anywhere-desktop [status|start|stop|restart|logs [N]|url]");
+            Some(2)
+        }
+    }
+}
+
+/// Re-run this binary with a reply file so the single-instance plugin
+/// forwards it, then print whatever the running instance writes back.
+fn forward(argv: &[String]) -> i32 {
+    let dir = config::data_dir().join("cli");
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        eprintln!("Failed to create {}: {e}", dir.display());
+        return 1;
+    }
+    let reply = dir.join(format!("reply-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&reply);
+
+    let status = std::env::current_exe().and_then(|exe| {
+        std::process::Command::new(exe)
+            .args(&argv[1..])
+            .arg(REPLY_FLAG)
+            .arg(&reply)
+            .status()
+    });
+    let forwarded = match status {
+        Ok(status) => status.success(),
+        Err(e) => {
+            eprintln!("Failed to contact the running instance: {e}");
+            return 1;
+        }
+    };
+
+    let deadline = Instant::now() + REPLY_TIMEOUT;
+    let contents = loop {
+        if let Ok(contents) = std::fs::read_to_string(&reply) {
+            break contents;
+        }
+        // The child only exits non-zero without replying if it never got
+        // as far as forwarding; nobody is going to answer.
+        if !forwarded {
+            eprintln!("Failed to contact the running instance");
+            return 1;
+        }
+        if Instant::now() >= deadline {
+            eprintln!("Timed out waiting for the running instance to respond");
+            return 1;
+        }
+        std::thread::sleep(REPLY_POLL_INTERVAL);
+    };
+    let _ = std::fs::remove_file(&reply);
+
+    match serde_json::from_str::<Reply>(&contents) {
+        Ok(Reply { ok: true, output }) => {
+            print!("{output}");
+            0
+        }
+        Ok(Reply { ok: false, output }) => {
+            eprint!("{output}");
+            1
+        }
+        Err(e) => {
+            eprintln!("Unreadable reply from the running instance: {e}");
+            1
+        }
+    }
+}
+
+/// Write the reply atomically, so the client never reads a partial file.
+fn write_reply(path: &Path, ok: bool, output: String) {
+    let output = if output.ends_with('\n') || output.is_empty() {
+        output
+    } else {
+        format!("{output}\n")
+    };
+    let tmp = path.with_extension("tmp");
+    let result = serde_json::to_string(&Reply { ok, output })
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&tmp, json).map_err(|e| e.to_string()))
+        .and_then(|_| std::fs::rename(&tmp, path).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        eprintln!("[cli] Failed to write reply to {}: {e}", path.display());
+    }
+}
+
+async fn execute(app: AppHandle, command: Command) -> Result<String, String> {
+    match command {
+        Command::Status => Ok(server::get_server_status(app).await?.to_string()),
+        Command::Start => {
+            server::start_server(app.clone()).await?;
+            Ok(server::get_server_status(app).await?.to_string())
+        }
+        Command::Stop => {
+            let report = server::stop_server(app).await?;
+            Ok(match report.forced_pids.as_slice() {
+                [] => "Server stopped".to_string(),
+                pids => format!("Server stopped (force-killed {pids:?})"),
+            })
+        }
+        Command::Restart => {
+            server::restart_server(app.clone()).await?;
+            Ok(server::get_server_status(app).await?.to_string())
+        }
+        Command::Logs { lines } => Ok(logs::get_server_logs(Some(lines), None, None).await?.text),
+        Command::Url => server::get_server_url(app)
+            .await?
+            .ok_or_else(|| "Server is not running".to_string()),
+    }
+}
+
+/// Run a CLI command forwarded by the single-instance plugin. Returns false
+/// if `argv` isn't a CLI invocation, e.g. a plain second launch.
+pub fn handle_forwarded(app: &AppHandle, argv: Vec<String>) -> bool {
+    let Some(reply) = reply_path(&argv) else {
+        return false;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = match parse(&argv) {
+            Some(Ok(command)) => execute(app, command).await,
+            Some(Err(e)) => Err(e),
+            None => Err("No command given".to_string()),
+        };
+        match result {
+            Ok(output) => write_reply(&reply, true, output),
+            Err(e) => write_reply(&reply, false, e),
+        }
+    });
+    true
+}
+
+/// Called from setup when this forwarded child turned out to be the only
+/// instance: there is nothing to control, so say so and quit.
+pub fn reply_not_running(app: &AppHandle) -> bool {
+    let Some(reply) = reply_path(&std::env::args().collect::<Vec<_>>()) else {
+        return false;
+    };
+    write_reply(&reply, false, "Yep Anywhere is not running".to_string());
+    app.exit(1);
+    true
+}
+
+/// Whether this process is a forwarded CLI child (and so shouldn't show a
+/// window or tray even briefly).
+pub fn is_forwarded() -> bool {
+    reply_path(&std::env::args().collect::<Vec<_>>()).is_some()
+}
@@ -0,0 +1,82 @@
+use semver::{Version, VersionReq};
+use serde::Serialize;
+
+use crate::server;
+
+/// Server versions this desktop shell can drive: the desktop-token auth,
+/// `/health` and the environment contract must match what we pass in.
+/// Bump together with any change to that contract.
+pub const SUPPORTED_SERVER_VERSIONS: &str = ">=0.4.0, <0.5.0";
+
+/// The supported range in npm syntax, for `bun install yepanywhere@<range>`.
+pub fn npm_range() -> String {
+    SUPPORTED_SERVER_VERSIONS.replace(", ", " ")
+}
+
+/// `SUPPORTED_SERVER_VERSIONS` as a requirement to match versions against.
+pub fn supported_req() -> VersionReq {
+    VersionReq::parse(SUPPORTED_SERVER_VERSIONS).expect("valid version range")
+}
+
+/// Result of checking the installed server against `SUPPORTED_SERVER_VERSIONS`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerCompatibility {
+    /// Version from the installed package's `package.json`, if readable.
+    pub installed: Option<String>,
+    pub supported: &'static str,
+    pub compatible: bool,
+    /// True if installing a version from the supported range would fix it,
+    /// i.e. the installed server is older than the range.
+    pub upgrade_available: bool,
+}
+
+/// Check the installed server's version. Dev mode always passes, since the
+/// checkout is the source of both halves.
+pub fn check() -> ServerCompatibility {
+    let installed = server::server_version();
+    if crate::config::dev_dir().is_some() {
+        return ServerCompatibility {
+            installed,
+            supported: SUPPORTED_SERVER_VERSIONS,
+            compatible: true,
+            upgrade_available: false,
+        };
+    }
+
+    let req = supported_req();
+    let parsed = installed.as_deref().and_then(|v| Version::parse(v).ok());
+    let compatible = parsed.as_ref().is_some_and(|v| req.matches(v));
+    // An unreadable version is treated as old: reinstalling is the fix.
+    let upgrade_available =
+        !compatible && parsed.is_none_or(|v| req.comparators.iter().all(|c| !newer_than(&v, c)));
+
+    ServerCompatibility {
+        installed,
+        supported: SUPPORTED_SERVER_VERSIONS,
+        compatible,
+        upgrade_available,
+    }
+}
+
+/// Whether `version` is past an upper-bound comparator such as `<0.5.0`.
+fn newer_than(version: &Version, comparator: &semver::Comparator) -> bool {
+    use semver::Op;
+    if !matches!(comparator.op, Op::Less | Op::LessEq) {
+        return false;
+    }
+    let bound = Version::new(
+        comparator.major,
+        comparator.minor.unwrap_or(0),
+        comparator.patch.unwrap_or(0),
+    );
+    match comparator.op {
+        Op::Less => *version >= bound,
+        _ => *version > bound,
+    }
+}
+
+/// Whether the installed server works with this desktop shell.
+#[tauri::command]
+pub async fn check_server_compatibility() -> Result<ServerCompatibility, String> {
+    Ok(check())
+}
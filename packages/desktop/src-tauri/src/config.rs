@@ -1,24 +1,346 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub setup_complete: bool,
+    /// Whether the app spawns its own server or attaches to an existing one.
+    #[serde(default)]
+    pub server_mode: ServerMode,
     pub agents: Vec<String>,
     /// User-specified port override. None = auto-pick a free port on each launch.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub port: Option<u16>,
+    /// How many times to retry on a fresh auto-picked port when the server
+    /// fails to bind. Ignored when `port` is set.
+    #[serde(default = "default_port_retries")]
+    pub port_retries: u32,
+    /// Which interfaces the server listens on besides localhost.
+    #[serde(default)]
+    pub bind_address: BindAddress,
     pub start_minimized: bool,
+    /// How long to wait for the server to answer `/health` after spawning it.
+    #[serde(default = "default_startup_timeout_secs")]
+    pub startup_timeout_secs: u64,
+    /// How the supervisor restarts the server after it crashes.
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    /// Health polling that restarts a server which is alive but not responding.
+    #[serde(default)]
+    pub watchdog: WatchdogPolicy,
+    /// Rotation settings for the captured server output under `logs/`.
+    #[serde(default)]
+    pub logs: LogRetention,
+    /// Sampling of the server's CPU and memory, and the optional memory limit.
+    #[serde(default)]
+    pub resources: ResourcePolicy,
+    /// How long to wait for the server's process group to exit after SIGTERM
+    /// before force-killing it.
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
+    /// Extra environment for the managed server (proxies, API keys, feature flags).
+    #[serde(default)]
+    pub env: BTreeMap<String, EnvVar>,
+    /// Extra command-line arguments for the managed server.
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+    /// Which versions of the npm components the installer sets up.
+    #[serde(default)]
+    pub versions: ComponentVersions,
+    /// Bun or Node binary to use when the bundled sidecar is missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime_path: Option<String>,
+    /// Refuse to install a downloaded binary whose release publishes no
+    /// checksum for it. Published checksums are always verified.
+    #[serde(default)]
+    pub require_checksums: bool,
+    /// How the server is launched from source when `YEP_DEV_DIR` is set.
+    #[serde(default)]
+    pub dev: DevLauncher,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvVar {
+    pub value: String,
+    /// Secret values are redacted when the config is sent to the frontend.
+    #[serde(default)]
+    pub secret: bool,
+}
+
+/// Placeholder sent to the frontend in place of secret values.
+pub const REDACTED: &str = "********";
+
+impl AppConfig {
+    /// Copy of the config that is safe to hand to the frontend.
+    pub fn redacted(&self) -> Self {
+        let mut cfg = self.clone();
+        for var in cfg.env.values_mut() {
+            if var.secret {
+                var.value = REDACTED.to_string();
+            }
+        }
+        cfg
+    }
+
+    /// Put back secret values the frontend sent as the redaction placeholder.
+    pub fn restore_secrets(&mut self, stored: &AppConfig) {
+        for (key, var) in self.env.iter_mut() {
+            if var.secret && var.value == REDACTED {
+                if let Some(old) = stored.env.get(key) {
+                    var.value = old.value.clone();
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ServerMode {
+    /// Spawn and supervise a local server (the default).
+    #[default]
+    Managed,
+    /// Use a server that runs elsewhere, e.g. under systemd or on another machine.
+    External {
+        /// Base URL, e.g. `http://workstation:3400`.
+        url: String,
+        /// The server's `DESKTOP_AUTH_TOKEN`, if it has one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BindAddress {
+    /// Only reachable from this machine (the default).
+    #[default]
+    Loopback,
+    /// Reachable on every IPv4 interface (`0.0.0.0`), e.g. from a phone on the same Wi-Fi.
+    AllInterfaces,
+    /// Reachable only on the named interface's address, e.g. `en0`.
+    Interface { name: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestartPolicy {
+    /// Restart the server automatically when it exits unexpectedly.
+    pub enabled: bool,
+    /// Give up once this many crashes happen within `crash_window_secs`.
+    pub max_crashes: u32,
+    pub crash_window_secs: u64,
+    /// Delay before the first restart; doubles with each crash in the window.
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_crashes: 5,
+            crash_window_secs: 300,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogPolicy {
+    pub enabled: bool,
+    /// How often to poll `/health` once the server is ready.
+    pub interval_secs: u64,
+    /// How long a single poll may take before it counts as a failure.
+    pub timeout_secs: u64,
+    /// Restart after this many consecutive failed polls.
+    pub max_failures: u32,
+}
+
+impl Default for WatchdogPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 15,
+            timeout_secs: 5,
+            max_failures: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogRetention {
+    /// Rotate the active log file once it would grow past this size.
+    pub max_file_bytes: u64,
+    /// Number of rotated files to keep next to the active one.
+    pub max_files: u32,
+    /// Delete rotated files older than this.
+    pub max_age_days: u64,
+}
+
+impl Default for LogRetention {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: 5 * 1024 * 1024,
+            max_files: 5,
+            max_age_days: 14,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourcePolicy {
+    /// How often to sample the server's process tree.
+    pub sample_interval_secs: u64,
+    /// Restart the server gracefully once its process tree's resident memory
+    /// exceeds this many MiB. None = no limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_limit_mb: Option<u64>,
+    /// How many consecutive samples must be over the limit before restarting,
+    /// so a short spike doesn't trigger a restart.
+    pub memory_limit_samples: u32,
+}
+
+impl Default for ResourcePolicy {
+    fn default() -> Self {
+        Self {
+            sample_interval_secs: 5,
+            memory_limit_mb: None,
+            memory_limit_samples: 3,
+        }
+    }
+}
+
+/// npm version specs: an exact version (`1.2.3`), a semver range (`^1.2`)
+/// or a dist-tag (`latest`, `next`). None = the installer's default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComponentVersions {
+    /// The `yepanywhere` server. Defaults to the range this app supports;
+    /// other specs install the newest version they allow within that range.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// `@anthropic-ai/claude-code`. Defaults to `latest`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claude: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DevLauncher {
+    /// Shell command that starts the dev server; `extra_args` are appended.
+    pub command: String,
+    /// Shell that runs `command`. None = `$SHELL`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    /// Run the shell as a login shell, so version managers put pnpm/node on
+    /// PATH (GUI apps start with a minimal one).
+    pub login_shell: bool,
+    /// Working directory, absolute or relative to the dev dir. None = the dev dir.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    pub watch: DevWatch,
+}
+
+impl Default for DevLauncher {
+    fn default() -> Self {
+        Self {
+            command: "pnpm dev".to_string(),
+            shell: None,
+            login_shell: true,
+            cwd: None,
+            watch: DevWatch::default(),
+        }
+    }
+}
+
+impl DevLauncher {
+    pub fn shell(&self) -> String {
+        self.shell
+            .clone()
+            .or_else(|| std::env::var("SHELL").ok())
+            .unwrap_or_else(|| {
+                if cfg!(target_os = "macos") {
+                    "/bin/zsh".to_string()
+                } else {
+                    "/bin/sh".to_string()
+                }
+            })
+    }
+
+    pub fn cwd(&self, dev_dir: &Path) -> PathBuf {
+        match &self.cwd {
+            Some(cwd) => dev_dir.join(cwd),
+            None => dev_dir.to_path_buf(),
+        }
+    }
+}
+
+/// Restart the dev server when files under `paths` change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DevWatch {
+    pub enabled: bool,
+    /// Subpaths of the dev dir to watch recursively.
+    pub paths: Vec<String>,
+    /// Wait for this long without further changes before restarting.
+    pub debounce_ms: u64,
+}
+
+impl Default for DevWatch {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            paths: vec![
+                "packages/server/src".to_string(),
+                "packages/shared/src".to_string(),
+            ],
+            debounce_ms: 500,
+        }
+    }
+}
+
+fn default_port_retries() -> u32 {
+    3
+}
+
+fn default_startup_timeout_secs() -> u64 {
+    30
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    10
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             setup_complete: false,
+            server_mode: ServerMode::default(),
             agents: vec![],
             port: None,
+            port_retries: default_port_retries(),
+            bind_address: BindAddress::default(),
             start_minimized: false,
+            startup_timeout_secs: default_startup_timeout_secs(),
+            restart: RestartPolicy::default(),
+            watchdog: WatchdogPolicy::default(),
+            logs: LogRetention::default(),
+            resources: ResourcePolicy::default(),
+            shutdown_timeout_secs: default_shutdown_timeout_secs(),
+            env: BTreeMap::new(),
+            extra_args: vec![],
+            versions: ComponentVersions::default(),
+            runtime_path: None,
+            require_checksums: false,
+            dev: DevLauncher::default(),
         }
     }
 }
@@ -36,11 +358,23 @@ pub fn bin_dir() -> PathBuf {
     data_dir().join("bin")
 }
 
+/// Executables of npm packages installed into the data dir (e.g. `claude`).
+pub fn node_bin_dir() -> PathBuf {
+    data_dir().join("node_modules").join(".bin")
+}
+
 /// If `YEP_DEV_DIR` is set, run from local source instead of installed npm package.
 pub fn dev_dir() -> Option<PathBuf> {
     std::env::var("YEP_DEV_DIR").ok().map(PathBuf::from)
 }
 
+/// Whether the app was started with `--headless`: no window or tray, server
+/// output on stdout.
+pub fn headless() -> bool {
+    static HEADLESS: OnceLock<bool> = OnceLock::new();
+    *HEADLESS.get_or_init(|| std::env::args().skip(1).any(|arg| arg == "--headless"))
+}
+
 pub fn load_config() -> AppConfig {
     let path = config_path();
     if path.exists() {
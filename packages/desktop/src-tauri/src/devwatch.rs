@@ -0,0 +1,105 @@
+use crate::config;
+use crate::host::Host;
+use crate::server::{self, ServerStatus};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use std::path::{Component, Path};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Directories whose churn never warrants a restart.
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", ".turbo"];
+
+/// Keeps the dev-dir watcher alive for the lifetime of the app.
+pub struct DevWatchState {
+    watcher: Mutex<Option<Debouncer<RecommendedWatcher>>>,
+}
+
+impl DevWatchState {
+    pub fn new() -> Self {
+        Self {
+            watcher: Mutex::new(None),
+        }
+    }
+}
+
+fn ignored(path: &Path) -> bool {
+    path.components().any(|c| match c {
+        Component::Normal(name) => IGNORED_DIRS.iter().any(|dir| name == *dir),
+        _ => false,
+    })
+}
+
+/// Whether a file change should bring the server back: it's running or
+/// crashed (e.g. on the syntax error the change is fixing), not stopped on
+/// purpose or waiting for setup.
+fn should_restart(host: &Host) -> bool {
+    let status = host.server().status.lock().map(|s| s.clone());
+    matches!(
+        status,
+        Ok(ServerStatus::Ready { .. } | ServerStatus::Starting | ServerStatus::Crashed { .. })
+    )
+}
+
+/// Watch the configured subpaths of the dev dir and restart the dev server,
+/// debounced, when files under them change. No-op outside dev mode or when
+/// `dev.watch.enabled` is off; config changes apply on the next app launch.
+pub fn start(host: &Host) {
+    let Some(dev_dir) = config::dev_dir() else {
+        return;
+    };
+    let watch = config::load_config().dev.watch;
+    if !watch.enabled {
+        return;
+    }
+
+    let handle = host.clone();
+    let restarting = Arc::new(AtomicBool::new(false));
+    let debounce = Duration::from_millis(watch.debounce_ms);
+    let result = new_debouncer(debounce, move |result: DebounceEventResult| {
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                eprintln!("[dev] Watch error: {e}");
+                return;
+            }
+        };
+        let Some(changed) = events.iter().find(|e| !ignored(&e.path)) else {
+            return;
+        };
+        if !should_restart(&handle) || restarting.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        println!(
+            "[dev] {} changed, restarting dev server",
+            changed.path.display()
+        );
+        let host = handle.clone();
+        let restarting = restarting.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = server::restart(&host).await {
+                eprintln!("[dev] Restart failed: {e}");
+            }
+            restarting.store(false, Ordering::SeqCst);
+        });
+    });
+    let mut debouncer = match result {
+        Ok(debouncer) => debouncer,
+        Err(e) => {
+            eprintln!("[dev] Failed to start file watcher: {e}");
+            return;
+        }
+    };
+
+    for subpath in &watch.paths {
+        let path = dev_dir.join(subpath);
+        if let Err(e) = debouncer.watcher().watch(&path, RecursiveMode::Recursive) {
+            eprintln!("[dev] Not watching {}: {e}", path.display());
+        }
+    }
+
+    if let Ok(mut lock) = host.devwatch().watcher.lock() {
+        *lock = Some(debouncer);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::host::Host;
+use crate::{config, devwatch, resources, server};
+
+/// Run the server under the supervisor until SIGINT/SIGTERM, for `--headless`
+/// runs. No Tauri app is built, so there's no window, tray or GTK, and no
+/// display server is needed. Returns the process exit code.
+///
+/// Status and server output go to stdout. Without the single-instance plugin,
+/// CLI subcommands can't reach a headless instance; another instance's start
+/// fails while this one owns the server in the PID file.
+pub fn run() -> i32 {
+    println!(
+        "[desktop] Running headless, data dir {}",
+        config::data_dir().display()
+    );
+
+    let host = Host::headless();
+    tauri::async_runtime::block_on(async move {
+        // Installed before the first launch, so a signal during startup still
+        // goes through `server::stop` instead of orphaning the server.
+        let mut signals = Signals::install();
+        tauri::async_runtime::spawn(resources::monitor(host.clone()));
+        devwatch::start(&host);
+
+        // Without a wizard there's nothing to wait for: a missing install
+        // shows up as "Setup required" and a non-zero exit.
+        let started = tokio::select! {
+            result = server::start_supervised(&host) => Some(result),
+            _ = signals.recv() => None,
+        };
+        let code = match started {
+            Some(Ok(())) => {
+                signals.recv().await;
+                0
+            }
+            Some(Err(e)) => {
+                eprintln!("[server] Failed to start: {e}");
+                1
+            }
+            None => 0,
+        };
+
+        println!("[desktop] Shutting down");
+        if let Err(e) = server::stop(&host).await {
+            eprintln!("[server] Failed to stop: {e}");
+        }
+        code
+    })
+}
+
+/// SIGINT and SIGTERM handlers; their default action would kill us without
+/// stopping the server's process group.
+#[cfg(unix)]
+struct Signals(Option<(tokio::signal::unix::Signal, tokio::signal::unix::Signal)>);
+
+#[cfg(unix)]
+impl Signals {
+    fn install() -> Self {
+        use tokio::signal::unix::{signal, SignalKind};
+        match (
+            signal(SignalKind::interrupt()),
+            signal(SignalKind::terminate()),
+        ) {
+            (Ok(interrupt), Ok(terminate)) => Signals(Some((interrupt, terminate))),
+            _ => {
+                eprintln!("[desktop] Could not install signal handlers");
+                Signals(None)
+            }
+        }
+    }
+
+    async fn recv(&mut self) {
+        let Some((interrupt, terminate)) = &mut self.0 else {
+            return std::future::pending().await;
+        };
+        tokio::select! {
+            _ = interrupt.recv() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct Signals;
+
+#[cfg(not(unix))]
+impl Signals {
+    fn install() -> Self {
+        Signals
+    }
+
+    async fn recv(&mut self) {
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::config;
+use crate::host::Host;
+use crate::server::{self, ServerStatus};
+use serde::Serialize;
+use std::time::Duration;
+
+/// Per-request timeout for a single `/health` poll.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+/// How often an external server's `/health` is checked.
+const EXTERNAL_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// HTTP client for `/health` polls, with a short per-request timeout.
+pub fn client() -> reqwest::Client {
+    client_with_timeout(REQUEST_TIMEOUT)
+}
+
+fn client_with_timeout(timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .unwrap_or_default()
+}
+
+/// Whether the server at `base_url` answers `/health` with a success status.
+pub async fn is_healthy(client: &reqwest::Client, base_url: &str) -> bool {
+    let url = format!("{}/health", base_url.trim_end_matches('/'));
+    matches!(client.get(url).send().await, Ok(resp) if resp.status().is_success())
+}
+
+/// Port of an external server URL, falling back to the scheme's default.
+pub fn url_port(url: &str) -> Option<u16> {
+    reqwest::Url::parse(url).ok()?.port_or_known_default()
+}
+
+/// Reflect an external server's `/health` in the server status until
+/// `generation` is superseded by a restart or a mode change.
+pub async fn monitor_external(host: Host, url: String, generation: u64) {
+    let state = host.server();
+    let client = client();
+    let port = url_port(&url).unwrap_or(80);
+
+    loop {
+        let healthy = is_healthy(&client, &url).await;
+        if state.generation() != generation {
+            return;
+        }
+        let current = state.status.lock().map(|s| s.clone()).ok();
+        match (healthy, current) {
+            (true, Some(ServerStatus::Ready { .. })) => {}
+            (true, _) => server::set_status(
+                &host,
+                ServerStatus::Ready {
+                    port,
+                    pid: None,
+                    since: server::now_ms(),
+                },
+            ),
+            (false, Some(ServerStatus::Unreachable { .. })) => {}
+            (false, _) => server::set_status(
+                &host,
+                ServerStatus::Unreachable {
+                    url: url.clone(),
+                    since: server::now_ms(),
+                },
+            ),
+        }
+        tokio::time::sleep(EXTERNAL_POLL_INTERVAL).await;
+    }
+}
+
+/// Emitted as `server-unresponsive` just before the watchdog restarts a hung server.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerUnresponsive {
+    pub failures: u32,
+    pub timeout_secs: u64,
+}
+
+/// Poll the managed server's `/health` while it is ready, and restart it
+/// through the graceful shutdown path after `max_failures` consecutive
+/// failures, e.g. when its event loop is wedged. Runs until `generation`
+/// is superseded.
+pub async fn watchdog(host: Host, generation: u64) {
+    let state = host.server();
+    let mut failures = 0u32;
+
+    loop {
+        let policy = config::load_config().watchdog;
+        tokio::time::sleep(Duration::from_secs(policy.interval_secs.max(1))).await;
+        if state.generation() != generation {
+            return;
+        }
+        let ready = matches!(
+            state.status.lock().map(|s| s.clone()),
+            Ok(ServerStatus::Ready { .. })
+        );
+        let port = state.port.lock().ok().and_then(|p| *p);
+        let (true, true, Some(port)) = (policy.enabled, ready, port) else {
+            failures = 0;
+            continue;
+        };
+
+        let client = client_with_timeout(Duration::from_secs(policy.timeout_secs.max(1)));
+        if is_healthy(&client, &format!("http://127.0.0.1:{port}")).await {
+            failures = 0;
+            continue;
+        }
+        failures += 1;
+        if state.generation() != generation || failures < policy.max_failures.max(1) {
+            continue;
+        }
+
+        eprintln!(
+            "[server] /health failed {failures} times in a row ({}s timeout), restarting unresponsive server",
+            policy.timeout_secs
+        );
+        host.emit(
+            "server-unresponsive",
+            ServerUnresponsive {
+                failures,
+                timeout_secs: policy.timeout_secs,
+            },
+        );
+        server::spawn_restart(host.clone(), "Watchdog");
+        // The restarted server gets a watchdog of its own.
+        return;
+    }
+}
@@ -0,0 +1,82 @@
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::devwatch::DevWatchState;
+use crate::logs::LogState;
+use crate::resources::ResourceState;
+use crate::server::ServerState;
+
+/// What the server supervisor, its monitors and output capture run against:
+/// the Tauri app, or for `--headless` just their state, with no GUI at all.
+/// Cheap to clone.
+#[derive(Clone)]
+pub enum Host {
+    /// State is managed by Tauri; events go to the webview and the tray.
+    App(AppHandle),
+    /// No Tauri app: state lives here, status goes to stdout and events are dropped.
+    Headless(Arc<HeadlessState>),
+}
+
+pub struct HeadlessState {
+    server: ServerState,
+    logs: LogState,
+    resources: ResourceState,
+    devwatch: DevWatchState,
+}
+
+impl Host {
+    pub fn headless() -> Self {
+        Host::Headless(Arc::new(HeadlessState {
+            server: ServerState::new(),
+            logs: LogState::new(),
+            resources: ResourceState::new(),
+            devwatch: DevWatchState::new(),
+        }))
+    }
+
+    pub fn is_headless(&self) -> bool {
+        matches!(self, Host::Headless(_))
+    }
+
+    pub fn server(&self) -> &ServerState {
+        match self {
+            Host::App(app) => app.state::<ServerState>().inner(),
+            Host::Headless(state) => &state.server,
+        }
+    }
+
+    pub fn logs(&self) -> &LogState {
+        match self {
+            Host::App(app) => app.state::<LogState>().inner(),
+            Host::Headless(state) => &state.logs,
+        }
+    }
+
+    pub fn resources(&self) -> &ResourceState {
+        match self {
+            Host::App(app) => app.state::<ResourceState>().inner(),
+            Host::Headless(state) => &state.resources,
+        }
+    }
+
+    pub fn devwatch(&self) -> &DevWatchState {
+        match self {
+            Host::App(app) => app.state::<DevWatchState>().inner(),
+            Host::Headless(state) => &state.devwatch,
+        }
+    }
+
+    /// Emit an event to the webview; a no-op when headless.
+    pub fn emit<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        if let Host::App(app) = self {
+            let _ = app.emit(event, payload);
+        }
+    }
+}
+
+impl From<&AppHandle> for Host {
+    fn from(app: &AppHandle) -> Self {
+        Host::App(app.clone())
+    }
+}
@@ -1,30 +1,135 @@
 use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::fmt;
 use std::fs;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
-use tokio::process::Command;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
 
-use crate::config;
+use crate::{compat, config, logs, manifest, runtime, updates};
 
-#[derive(Clone, Serialize)]
+/// Stages of a package install, in order. Inferred from bun/npm output.
+const INSTALL_PHASES: &[&str] = &["resolving", "downloading", "linking"];
+/// Lines of stderr kept for the failure summary.
+const ERROR_TAIL_LINES: usize = 20;
+/// Minimum time between download progress events.
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Default, Serialize)]
 struct InstallProgress {
     agent: String,
     status: String,
     message: String,
+    /// Current install phase, for `log` and `error` events.
+    phase: Option<&'static str>,
+    /// Milliseconds since the install started.
+    elapsed_ms: Option<u64>,
+    /// `stdout` or `stderr`, for `log` events.
+    stream: Option<&'static str>,
+    /// Set on the final `error` event of a package install.
+    error: Option<InstallError>,
+    /// Set on `downloading` events of a binary download.
+    download: Option<DownloadProgress>,
+    /// Set on the `error` event when a download fails verification.
+    integrity: Option<IntegrityError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub received_bytes: u64,
+    /// From Content-Length; `None` if the server didn't send one.
+    pub total_bytes: Option<u64>,
+    /// Average since the download started.
+    pub bytes_per_sec: f64,
+    /// `None` until the total and a rate are known.
+    pub eta_secs: Option<f64>,
 }
 
-/// Resolve the bundled Bun sidecar binary path.
-/// Tauri places externalBin sidecars next to the main executable (Contents/MacOS/).
-fn bun_path(_app: &AppHandle) -> Result<std::path::PathBuf, String> {
-    let exe = std::env::current_exe().map_err(|e| format!("Could not resolve executable: {e}"))?;
-    let exe_dir = exe
-        .parent()
-        .ok_or_else(|| "Could not resolve executable directory".to_string())?;
-    let bin_name = if cfg!(windows) { "bun.exe" } else { "bun" };
-    let path = exe_dir.join(bin_name);
-    if path.exists() {
-        return Ok(path);
+/// Why a package install failed, for display without scraping the log.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallError {
+    pub package: String,
+    pub spec: String,
+    /// `None` if the installer was killed by a signal.
+    pub exit_code: Option<i32>,
+    /// The most relevant line of output, usually bun's `error: ...` line.
+    pub summary: String,
+    /// The last lines of stderr.
+    pub stderr_tail: Vec<String>,
+}
+
+impl InstallError {
+    fn new(package: &str, spec: &str, exit_code: Option<i32>, stderr_tail: Vec<String>) -> Self {
+        let summary = stderr_tail
+            .iter()
+            .rev()
+            .find(|line| line.to_lowercase().contains("error"))
+            .or_else(|| stderr_tail.last())
+            .cloned()
+            .unwrap_or_else(|| match exit_code {
+                Some(code) => format!("Installer exited with code {code}"),
+                None => "Installer was terminated".to_string(),
+            });
+        Self {
+            package: package.to_string(),
+            spec: spec.to_string(),
+            exit_code,
+            summary,
+            stderr_tail,
+        }
     }
-    Err(format!("Bun sidecar not found at {}", path.display()))
+}
+
+/// A downloaded binary that couldn't be verified against its release.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IntegrityError {
+    /// The download's SHA-256 differs from the published one.
+    Mismatch {
+        asset: String,
+        expected: String,
+        actual: String,
+    },
+    /// The release publishes no checksum and `require_checksums` is on.
+    MissingChecksum { asset: String },
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::Mismatch {
+                asset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Checksum mismatch for {asset}: expected {expected}, got {actual}"
+            ),
+            IntegrityError::MissingChecksum { asset } => write!(
+                f,
+                "No checksum published for {asset}, and require_checksums is enabled"
+            ),
+        }
+    }
+}
+
+fn emit_integrity_error(app: &AppHandle, agent: &str, error: IntegrityError) -> String {
+    let message = error.to_string();
+    let _ = app.emit(
+        "install-progress",
+        InstallProgress {
+            agent: agent.to_string(),
+            status: "error".to_string(),
+            message: message.clone(),
+            integrity: Some(error),
+            ..Default::default()
+        },
+    );
+    message
 }
 
 fn emit_progress(app: &AppHandle, agent: &str, status: &str, message: &str) {
@@ -34,73 +139,402 @@ fn emit_progress(app: &AppHandle, agent: &str, status: &str, message: &str) {
             agent: agent.to_string(),
             status: status.to_string(),
             message: message.to_string(),
+            ..Default::default()
         },
     );
 }
 
-#[tauri::command]
-pub async fn install_yep_server(app: AppHandle) -> Result<(), String> {
-    let bun = bun_path(&app)?;
+/// The install phase a line of bun or npm output marks the start of, if any.
+fn phase_of(line: &str) -> Option<&'static str> {
+    let line = line.to_lowercase();
+    if line.contains("saved lockfile")
+        || line.starts_with("installed ")
+        || line.starts_with("added ")
+        || line.contains("postinstall")
+    {
+        Some("linking")
+    } else if ["resolved", "download", "extract", "fetch"]
+        .iter()
+        .any(|word| line.contains(word))
+    {
+        Some("downloading")
+    } else {
+        None
+    }
+}
+
+/// Send each line of `reader` down `tx` until it closes.
+fn forward_lines<R>(
+    reader: R,
+    stream: &'static str,
+    tx: mpsc::UnboundedSender<(&'static str, String)>,
+) where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            let line = logs::strip_ansi(String::from_utf8_lossy(&buf).trim_end());
+            if !line.is_empty() && tx.send((stream, line)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// `name@spec` for the installer, with a blank spec treated as unset.
+fn version_spec(configured: Option<String>, default: impl FnOnce() -> String) -> String {
+    configured
+        .map(|spec| spec.trim().to_string())
+        .filter(|spec| !spec.is_empty())
+        .unwrap_or_else(default)
+}
+
+/// Version spec for the server install. Defaults to the range this shell
+/// supports, so a fresh install is never too new.
+pub(crate) fn server_spec() -> String {
+    version_spec(config::load_config().versions.server, compat::npm_range)
+}
+
+/// What to install for the server `spec`: the supported range as is, or
+/// anything else resolved to the newest published version inside that
+/// range, so a pinned tag or version can't install a server launch refuses.
+async fn server_install_spec(spec: &str) -> Result<String, String> {
+    if spec == compat::npm_range() {
+        return Ok(spec.to_string());
+    }
+    updates::npm_resolve(
+        &updates::client(),
+        "yepanywhere",
+        spec,
+        Some(&compat::supported_req()),
+    )
+    .await
+}
+
+/// Version spec for the Claude Code install.
+pub(crate) fn claude_spec() -> String {
+    version_spec(config::load_config().versions.claude, || {
+        "latest".to_string()
+    })
+}
+
+/// Install `package@install_spec` into the data dir and record what it
+/// resolved to, under the configured `spec` it came from.
+async fn install_npm(
+    app: &AppHandle,
+    agent: &str,
+    package: &str,
+    spec: &str,
+    install_spec: &str,
+) -> Result<(), String> {
+    let runtime = runtime::resolve().map_err(|e| e.to_string())?;
     let data_dir = config::data_dir();
     fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
 
-    emit_progress(&app, "yep", "installing", "Installing Yep Anywhere server...");
-
-    let output = Command::new(&bun)
-        .args(["install", "yepanywhere"])
+    let started = Instant::now();
+    let mut child = runtime
+        .install_command()
+        .arg(format!("{package}@{install_spec}"))
         .current_dir(&data_dir)
-        .output()
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run package install: {e}"))?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, "stdout", tx.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, "stderr", tx);
+    }
+
+    let mut phase = 0;
+    let mut stderr_tail = VecDeque::with_capacity(ERROR_TAIL_LINES);
+    while let Some((stream, line)) = rx.recv().await {
+        if let Some(next) =
+            phase_of(&line).and_then(|p| INSTALL_PHASES.iter().position(|&x| x == p))
+        {
+            phase = phase.max(next);
+        }
+        if stream == "stderr" {
+            if stderr_tail.len() == ERROR_TAIL_LINES {
+                stderr_tail.pop_front();
+            }
+            stderr_tail.push_back(line.clone());
+        }
+        let _ = app.emit(
+            "install-progress",
+            InstallProgress {
+                agent: agent.to_string(),
+                status: "log".to_string(),
+                message: line,
+                phase: Some(INSTALL_PHASES[phase]),
+                elapsed_ms: Some(started.elapsed().as_millis() as u64),
+                stream: Some(stream),
+                ..Default::default()
+            },
+        );
+    }
+
+    let status = child
+        .wait()
         .await
-        .map_err(|e| format!("Failed to run bun install: {e}"))?;
+        .map_err(|e| format!("Failed to run package install: {e}"))?;
+    if !status.success() {
+        let error = InstallError::new(package, install_spec, status.code(), stderr_tail.into());
+        let message = format!("Install failed: {}", error.summary);
+        let _ = app.emit(
+            "install-progress",
+            InstallProgress {
+                agent: agent.to_string(),
+                status: "error".to_string(),
+                message,
+                phase: Some(INSTALL_PHASES[phase]),
+                elapsed_ms: Some(started.elapsed().as_millis() as u64),
+                error: Some(error.clone()),
+                ..Default::default()
+            },
+        );
+        return Err(format!("Package install failed: {}", error.summary));
+    }
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        emit_progress(&app, "yep", "error", &format!("Install failed: {stderr}"));
-        return Err(format!("bun install failed: {stderr}"));
+    match manifest::installed_version(package) {
+        Some(version) => manifest::record(package, spec, &version),
+        None => eprintln!(
+            "[installer] Installed {package}@{install_spec} but could not read its version"
+        ),
     }
+    Ok(())
+}
 
+#[tauri::command]
+pub async fn install_yep_server(app: AppHandle) -> Result<(), String> {
+    emit_progress(&app, "yep", "installing", "Installing Yep Anywhere server...");
+    let spec = server_spec();
+    let install_spec = server_install_spec(&spec).await.inspect_err(|e| {
+        emit_progress(&app, "yep", "error", e);
+    })?;
+    install_npm(&app, "yep", "yepanywhere", &spec, &install_spec).await?;
     emit_progress(&app, "yep", "done", "Yep Anywhere server installed");
     Ok(())
 }
 
 #[tauri::command]
 pub async fn install_claude(app: AppHandle) -> Result<(), String> {
-    let bun = bun_path(&app)?;
-    let data_dir = config::data_dir();
-    fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
+    let spec = claude_spec();
 
     emit_progress(&app, "claude", "installing", "Installing Claude Code...");
+    install_npm(&app, "claude", "@anthropic-ai/claude-code", &spec, &spec).await?;
+    emit_progress(&app, "claude", "done", "Claude Code installed");
+    Ok(())
+}
 
-    let output = Command::new(&bun)
-        .args(["install", "@anthropic-ai/claude-code"])
-        .current_dir(&data_dir)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run bun install: {e}"))?;
+/// Byte count for progress messages, e.g. `12.3 MB`.
+fn format_mb(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1_000_000.0)
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        emit_progress(
-            &app,
-            "claude",
-            "error",
-            &format!("Install failed: {stderr}"),
-        );
-        return Err(format!("bun install failed: {stderr}"));
+/// Stream `url` into `dest`, emitting `downloading` progress events for
+/// `agent` along the way, and return the file's SHA-256 as lowercase hex.
+/// Removes the partial file on failure.
+async fn download_to_file(
+    app: &AppHandle,
+    agent: &str,
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+) -> Result<String, String> {
+    let result = async {
+        let mut resp = client
+            .get(url)
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| format!("Download failed: {e}"))?;
+        let total_bytes = resp.content_length();
+        let mut file = tokio::fs::File::create(dest)
+            .await
+            .map_err(|e| format!("Failed to create {}: {e}", dest.display()))?;
+
+        let started = Instant::now();
+        let mut last_emit: Option<Instant> = None;
+        let mut received_bytes = 0u64;
+        let mut hasher = Sha256::new();
+        loop {
+            let chunk = resp
+                .chunk()
+                .await
+                .map_err(|e| format!("Download failed: {e}"))?;
+            let finished = chunk.is_none();
+            if let Some(chunk) = chunk {
+                file.write_all(&chunk)
+                    .await
+                    .map_err(|e| format!("Failed to write download: {e}"))?;
+                received_bytes += chunk.len() as u64;
+                hasher.update(&chunk);
+            }
+            if finished || last_emit.is_none_or(|t| t.elapsed() >= DOWNLOAD_PROGRESS_INTERVAL) {
+                last_emit = Some(Instant::now());
+                let elapsed = started.elapsed();
+                let bytes_per_sec = received_bytes as f64 / elapsed.as_secs_f64().max(0.001);
+                let eta_secs = total_bytes
+                    .filter(|_| bytes_per_sec > 0.0)
+                    .map(|total| total.saturating_sub(received_bytes) as f64 / bytes_per_sec);
+                let message = match total_bytes {
+                    Some(total) => format!(
+                        "Downloading... {} of {}",
+                        format_mb(received_bytes),
+                        format_mb(total)
+                    ),
+                    None => format!("Downloading... {}", format_mb(received_bytes)),
+                };
+                let _ = app.emit(
+                    "install-progress",
+                    InstallProgress {
+                        agent: agent.to_string(),
+                        status: "downloading".to_string(),
+                        message,
+                        elapsed_ms: Some(elapsed.as_millis() as u64),
+                        download: Some(DownloadProgress {
+                            received_bytes,
+                            total_bytes,
+                            bytes_per_sec,
+                            eta_secs,
+                        }),
+                        ..Default::default()
+                    },
+                );
+            }
+            if finished {
+                break;
+            }
+        }
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write download: {e}"))?;
+
+        if let Some(total) = total_bytes.filter(|&total| total != received_bytes) {
+            return Err(format!(
+                "Download incomplete: received {received_bytes} of {total} bytes"
+            ));
+        }
+        Ok(hex::encode(hasher.finalize()))
     }
+    .await;
 
-    emit_progress(&app, "claude", "done", "Claude Code installed");
-    Ok(())
+    if result.is_err() {
+        let _ = fs::remove_file(dest);
+    }
+    result
 }
 
-#[tauri::command]
-pub async fn install_codex(app: AppHandle) -> Result<(), String> {
-    let bin_dir = config::bin_dir();
-    fs::create_dir_all(&bin_dir).map_err(|e| e.to_string())?;
+/// The SHA-256 for `asset` in a checksum file: either a bare hash, or
+/// `sha256sum`-style lines of `<hash>  <name>`, where the name may carry a
+/// path. A `per_asset` file's single entry is taken whatever it is named.
+fn parse_checksum(contents: &str, asset: &str, per_asset: bool) -> Option<String> {
+    let is_hash = |s: &str| s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit());
+    let lines: Vec<(String, Option<&str>)> = contents
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next().filter(|h| is_hash(h))?;
+            // `sha256sum -b` marks binary-mode entries with `*`.
+            let name = parts.next().map(|n| n.trim_start_matches('*'));
+            Some((hash.to_lowercase(), name))
+        })
+        .collect();
+    let names_asset = |name: &str| Path::new(name).file_name() == Some(asset.as_ref());
+    if let Some((hash, _)) = lines.iter().find(|(_, name)| name.is_some_and(names_asset)) {
+        return Some(hash.clone());
+    }
+    // A lone entry in a per-asset file, or a lone unnamed hash.
+    match lines.as_slice() {
+        [(hash, name)] if per_asset || name.is_none() => Some(hash.clone()),
+        _ => None,
+    }
+}
 
-    emit_progress(&app, "codex", "installing", "Downloading Codex CLI...");
+/// Look up the published SHA-256 of `asset_name` among a release's assets:
+/// `<asset>.sha256` first, then a combined checksums file.
+async fn published_checksum(
+    client: &reqwest::Client,
+    assets: &[serde_json::Value],
+    asset_name: &str,
+) -> Result<Option<String>, String> {
+    let per_asset = format!("{asset_name}.sha256");
+    let mut candidates: Vec<&serde_json::Value> = assets
+        .iter()
+        .filter(|a| a["name"].as_str() == Some(per_asset.as_str()))
+        .collect();
+    candidates.extend(assets.iter().filter(|a| {
+        a["name"].as_str().is_some_and(|n| {
+            let n = n.to_lowercase();
+            n.contains("checksum") || n.contains("sha256sum")
+        })
+    }));
+
+    for checksum_asset in candidates {
+        let Some(url) = checksum_asset["browser_download_url"].as_str() else {
+            continue;
+        };
+        let is_per_asset = checksum_asset["name"].as_str() == Some(per_asset.as_str());
+        let contents = client
+            .get(url)
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| format!("Failed to fetch checksum: {e}"))?
+            .text()
+            .await
+            .map_err(|e| format!("Failed to fetch checksum: {e}"))?;
+        if let Some(hash) = parse_checksum(&contents, asset_name, is_per_asset) {
+            return Ok(Some(hash));
+        }
+    }
+    Ok(None)
+}
 
-    let client = reqwest::Client::new();
+/// Unpack the `codex` binary from a release tarball to `dest`.
+fn extract_codex(archive: &Path, dest: &Path) -> Result<(), String> {
+    use flate2::read::GzDecoder;
+    use tar::Archive;
+
+    let file = fs::File::open(archive).map_err(|e| format!("Failed to open archive: {e}"))?;
+    let mut archive = Archive::new(GzDecoder::new(std::io::BufReader::new(file)));
+
+    for entry in archive
+        .entries()
+        .map_err(|e| format!("Failed to read archive: {e}"))?
+    {
+        let mut entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+        let path = entry
+            .path()
+            .map_err(|e| format!("Failed to read path: {e}"))?;
+        if path.file_name().is_some_and(|n| {
+            let s = n.to_string_lossy();
+            s == "codex" || s.starts_with("codex-")
+        }) {
+            entry
+                .unpack(dest)
+                .map_err(|e| format!("Failed to extract codex: {e}"))?;
+            return Ok(());
+        }
+    }
+    Err("Could not find codex binary in archive".to_string())
+}
+
+/// The latest Codex CLI release from the GitHub releases API.
+pub(crate) async fn latest_codex_release(
+    client: &reqwest::Client,
+) -> Result<serde_json::Value, String> {
     let resp = client
         .get("https://api.github.com/repos/openai/codex/releases/latest")
         .header("User-Agent", "yep-anywhere-desktop")
@@ -108,10 +542,20 @@ pub async fn install_codex(app: AppHandle) -> Result<(), String> {
         .await
         .map_err(|e| format!("Failed to fetch release info: {e}"))?;
 
-    let release: serde_json::Value = resp
-        .json()
+    resp.json()
         .await
-        .map_err(|e| format!("Failed to parse release info: {e}"))?;
+        .map_err(|e| format!("Failed to parse release info: {e}"))
+}
+
+#[tauri::command]
+pub async fn install_codex(app: AppHandle) -> Result<(), String> {
+    let bin_dir = config::bin_dir();
+    fs::create_dir_all(&bin_dir).map_err(|e| e.to_string())?;
+
+    emit_progress(&app, "codex", "installing", "Downloading Codex CLI...");
+
+    let client = reqwest::Client::new();
+    let release = latest_codex_release(&client).await?;
 
     // Codex assets use Rust target triples: codex-{triple}.tar.gz (Unix) or codex-{triple}.exe (Windows)
     let triple = env!("TARGET_TRIPLE");
@@ -133,62 +577,49 @@ pub async fn install_codex(app: AppHandle) -> Result<(), String> {
         .as_str()
         .ok_or("No download URL")?;
 
-    emit_progress(&app, "codex", "downloading", "Downloading...");
-
-    let bytes = client
-        .get(download_url)
-        .send()
-        .await
-        .map_err(|e| format!("Download failed: {e}"))?
-        .bytes()
-        .await
-        .map_err(|e| format!("Download failed: {e}"))?;
-
     let codex_bin = if cfg!(windows) {
         bin_dir.join("codex.exe")
     } else {
         bin_dir.join("codex")
     };
 
-    if is_archive {
-        // Extract codex binary from tar.gz
-        emit_progress(&app, "codex", "extracting", "Extracting...");
-
-        use flate2::read::GzDecoder;
-        use std::io::Cursor;
-        use tar::Archive;
-
-        let decoder = GzDecoder::new(Cursor::new(bytes.as_ref()));
-        let mut archive = Archive::new(decoder);
-        let mut found = false;
-
-        for entry in archive
-            .entries()
-            .map_err(|e| format!("Failed to read archive: {e}"))?
-        {
-            let mut entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
-            let path = entry
-                .path()
-                .map_err(|e| format!("Failed to read path: {e}"))?;
-            if path.file_name().is_some_and(|n| {
-                let s = n.to_string_lossy();
-                s == "codex" || s.starts_with("codex-")
-            }) {
-                entry
-                    .unpack(&codex_bin)
-                    .map_err(|e| format!("Failed to extract codex: {e}"))?;
-                found = true;
-                break;
-            }
+    let expected = published_checksum(&client, assets, &asset_name).await?;
+    if expected.is_none() {
+        if config::load_config().require_checksums {
+            return Err(emit_integrity_error(
+                &app,
+                "codex",
+                IntegrityError::MissingChecksum { asset: asset_name },
+            ));
         }
+        eprintln!("[installer] No checksum published for {asset_name}; skipping verification");
+    }
 
-        if !found {
-            return Err("Could not find codex binary in archive".to_string());
-        }
-    } else {
-        fs::write(&codex_bin, &bytes).map_err(|e| format!("Failed to write binary: {e}"))?;
+    // Download next to the destination so the final rename stays on one filesystem.
+    let download = bin_dir.join(format!("{asset_name}.download"));
+    let actual = download_to_file(&app, "codex", &client, download_url, &download).await?;
+    if let Some(expected) = expected.filter(|expected| *expected != actual) {
+        let _ = fs::remove_file(&download);
+        return Err(emit_integrity_error(
+            &app,
+            "codex",
+            IntegrityError::Mismatch {
+                asset: asset_name,
+                expected,
+                actual,
+            },
+        ));
     }
 
+    let result = if is_archive {
+        emit_progress(&app, "codex", "extracting", "Extracting...");
+        extract_codex(&download, &codex_bin)
+    } else {
+        fs::rename(&download, &codex_bin).map_err(|e| format!("Failed to write binary: {e}"))
+    };
+    let _ = fs::remove_file(&download);
+    result?;
+
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
@@ -196,6 +627,10 @@ pub async fn install_codex(app: AppHandle) -> Result<(), String> {
             .map_err(|e| format!("Failed to set permissions: {e}"))?;
     }
 
+    if let Some(tag) = release["tag_name"].as_str() {
+        manifest::record("codex", "latest", tag);
+    }
+
     emit_progress(&app, "codex", "done", "Codex CLI installed");
     Ok(())
 }
@@ -204,10 +639,7 @@ pub async fn install_codex(app: AppHandle) -> Result<(), String> {
 pub async fn check_agent_installed(agent: String) -> Result<bool, String> {
     match agent.as_str() {
         "claude" => {
-            let path = config::data_dir()
-                .join("node_modules")
-                .join(".bin")
-                .join("claude");
+            let path = config::node_bin_dir().join("claude");
             Ok(path.exists())
         }
         "codex" => {
@@ -237,8 +669,8 @@ pub async fn check_agent_installed(agent: String) -> Result<bool, String> {
 /// Check if Claude is already authenticated by running `claude auth status`
 /// and parsing the JSON output. Returns true if `loggedIn` is true.
 #[tauri::command]
-pub async fn check_claude_auth(app: AppHandle) -> Result<bool, String> {
-    let bun = bun_path(&app)?;
+pub async fn check_claude_auth(_app: AppHandle) -> Result<bool, String> {
+    let runtime = runtime::resolve().map_err(|e| e.to_string())?;
     let data_dir = config::data_dir();
     let script = data_dir
         .join("node_modules")
@@ -250,8 +682,9 @@ pub async fn check_claude_auth(app: AppHandle) -> Result<bool, String> {
         return Ok(false);
     }
 
-    let output = Command::new(&bun)
-        .args([script.to_string_lossy().as_ref(), "auth", "status"])
+    let output = runtime
+        .script_command(&script)
+        .args(["auth", "status"])
         .output()
         .await
         .map_err(|e| format!("Failed to run claude auth status: {e}"))?;
@@ -267,3 +700,73 @@ pub async fn check_claude_auth(app: AppHandle) -> Result<bool, String> {
 
     Ok(text.contains("\"loggedIn\": true") || text.contains("\"loggedIn\":true"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASSET: &str = "codex-x86_64-unknown-linux-musl.tar.gz";
+    const HASH: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+    const OTHER: &str = "60303ae22b998861bce3b28f33eec1be758a213c86c93c076dbe9f558c11c752";
+
+    #[test]
+    fn bare_hash() {
+        assert_eq!(
+            parse_checksum(&format!("{HASH}\n"), ASSET, true).as_deref(),
+            Some(HASH)
+        );
+    }
+
+    #[test]
+    fn sha256sum_lines() {
+        let contents = format!("{OTHER}  codex-aarch64-apple-darwin.tar.gz\n{HASH}  {ASSET}\n");
+        assert_eq!(
+            parse_checksum(&contents, ASSET, false).as_deref(),
+            Some(HASH)
+        );
+    }
+
+    #[test]
+    fn sha256sum_binary_mode_lines() {
+        let contents = format!("{OTHER} *codex-aarch64-apple-darwin.tar.gz\n{HASH} *{ASSET}\n");
+        assert_eq!(
+            parse_checksum(&contents, ASSET, false).as_deref(),
+            Some(HASH)
+        );
+    }
+
+    #[test]
+    fn combined_file_without_asset() {
+        let contents = format!("{OTHER}  codex-aarch64-apple-darwin.tar.gz\n");
+        assert_eq!(parse_checksum(&contents, ASSET, false), None);
+    }
+
+    #[test]
+    fn names_with_path_prefix() {
+        let contents =
+            format!("{OTHER}  ./codex-aarch64-apple-darwin.tar.gz\n{HASH}  dist/{ASSET}\n");
+        assert_eq!(
+            parse_checksum(&contents, ASSET, false).as_deref(),
+            Some(HASH)
+        );
+    }
+
+    #[test]
+    fn per_asset_file_with_other_name() {
+        let contents = format!("{HASH}  ./codex.tar.gz\n");
+        assert_eq!(
+            parse_checksum(&contents, ASSET, true).as_deref(),
+            Some(HASH)
+        );
+        assert_eq!(parse_checksum(&contents, ASSET, false), None);
+    }
+
+    #[test]
+    fn uppercase_hash() {
+        let contents = format!("{}  {ASSET}\n", HASH.to_uppercase());
+        assert_eq!(
+            parse_checksum(&contents, ASSET, false).as_deref(),
+            Some(HASH)
+        );
+    }
+}
@@ -1,18 +1,33 @@
+mod cli;
+mod compat;
 mod config;
+mod devwatch;
+mod headless;
+mod health;
+mod host;
 mod installer;
+mod logs;
+mod manifest;
+mod network;
+mod pidfile;
+mod process;
 mod pty;
+mod resources;
+mod runtime;
 mod server;
 mod tray;
+mod updates;
 
 use tauri::Manager;
 
 #[tauri::command]
 fn get_config() -> Result<config::AppConfig, String> {
-    Ok(config::load_config())
+    Ok(config::load_config().redacted())
 }
 
 #[tauri::command]
-fn save_app_config(cfg: config::AppConfig) -> Result<(), String> {
+fn save_app_config(mut cfg: config::AppConfig) -> Result<(), String> {
+    cfg.restore_secrets(&config::load_config());
     config::save_config(&cfg)
 }
 
@@ -28,6 +43,14 @@ fn is_dev_mode() -> Option<String> {
 }
 
 pub fn run() {
+    if let Some(code) = cli::dispatch() {
+        std::process::exit(code);
+    }
+    if config::headless() {
+        std::process::exit(headless::run());
+    }
+    // A CLI child that wasn't forwarded only needs to report "not running".
+    let forwarded_cli = cli::is_forwarded();
     let mut builder = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
@@ -40,7 +63,10 @@ pub fn run() {
         builder = builder
             .plugin(tauri_plugin_updater::Builder::new().build())
             .plugin(tauri_plugin_process::init())
-            .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+                if cli::handle_forwarded(app, args) {
+                    return;
+                }
                 if let Some(window) = app.get_webview_window("main") {
                     let _ = window.show();
                     let _ = window.set_focus();
@@ -53,9 +79,17 @@ pub fn run() {
             .plugin(tauri_plugin_window_state::Builder::default().build());
     }
 
+    let mut context = tauri::generate_context!();
+    if forwarded_cli {
+        context.config_mut().app.windows.clear();
+    }
+
     builder
         .manage(server::ServerState::new())
         .manage(pty::PtyState::new())
+        .manage(logs::LogState::new())
+        .manage(resources::ResourceState::new())
+        .manage(devwatch::DevWatchState::new())
         .invoke_handler(tauri::generate_handler![
             get_config,
             save_app_config,
@@ -63,20 +97,41 @@ pub fn run() {
             is_dev_mode,
             server::start_server,
             server::stop_server,
+            server::restart_server,
             server::get_server_status,
             server::get_desktop_token,
             server::get_server_port,
+            server::get_server_url,
+            server::get_server_listen,
+            server::get_server_crashes,
+            compat::check_server_compatibility,
+            runtime::get_runtime,
+            resources::get_server_resources,
+            logs::get_server_logs,
+            logs::subscribe_server_logs,
+            logs::unsubscribe_server_logs,
+            network::list_network_addresses,
             installer::install_yep_server,
             installer::install_claude,
             installer::install_codex,
             installer::check_agent_installed,
             installer::check_claude_auth,
+            manifest::get_component_manifest,
+            updates::check_component_updates,
+            updates::upgrade_component,
             pty::spawn_pty,
             pty::write_pty,
             pty::resize_pty,
             pty::kill_pty,
         ])
-        .setup(|app| {
+        .setup(move |app| {
+            if forwarded_cli && cli::reply_not_running(app.handle()) {
+                return Ok(());
+            }
+            let host = host::Host::from(app.handle());
+            tauri::async_runtime::spawn(resources::monitor(host.clone()));
+            devwatch::start(&host);
+
             // Setup system tray
             tray::setup_tray(app.handle())?;
 
@@ -103,7 +158,7 @@ pub fn run() {
                 api.prevent_close();
             }
         })
-        .build(tauri::generate_context!())
+        .build(context)
         .expect("error while building tauri application")
         .run(|app_handle, event| {
             if let tauri::RunEvent::Exit = event {
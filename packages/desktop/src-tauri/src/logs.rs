@@ -0,0 +1,549 @@
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::config;
+use crate::host::Host;
+
+/// Name of the active log file; rotated copies are `server-output.1.log`, `.2.log`, ...
+/// (The server's own `LOG_TO_FILE` output uses `server.log` in the same directory.)
+const LOG_STEM: &str = "server-output";
+/// Start a fresh file at least this often, even if it is still small.
+const ROTATE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Upper bound on how much `get_server_logs` returns in one call.
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+const DEFAULT_TAIL_LINES: usize = 200;
+
+pub fn logs_dir() -> PathBuf {
+    config::data_dir().join("logs")
+}
+
+fn log_path(index: u32) -> PathBuf {
+    if index == 0 {
+        logs_dir().join(format!("{LOG_STEM}.log"))
+    } else {
+        logs_dir().join(format!("{LOG_STEM}.{index}.log"))
+    }
+}
+
+/// Append-only writer for the server's stdout/stderr with size- and age-based rotation.
+struct RotatingLog {
+    file: File,
+    size: u64,
+    opened_at: SystemTime,
+    retention: config::LogRetention,
+}
+
+impl RotatingLog {
+    fn open(retention: config::LogRetention) -> std::io::Result<Self> {
+        fs::create_dir_all(logs_dir())?;
+        let path = log_path(0);
+
+        // A leftover file from an old session that is already too old gets rotated away.
+        if let Ok(meta) = fs::metadata(&path) {
+            let stale = meta
+                .modified()
+                .ok()
+                .and_then(|m| m.elapsed().ok())
+                .is_some_and(|age| age > ROTATE_INTERVAL);
+            if stale {
+                shift_rotated(&retention)?;
+            }
+        }
+        prune_rotated(&retention);
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            file,
+            size,
+            opened_at: SystemTime::now(),
+            retention,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let len = line.len() as u64 + 1;
+        let too_big = self.size > 0 && self.size + len > self.retention.max_file_bytes;
+        let too_old = self
+            .opened_at
+            .elapsed()
+            .is_ok_and(|age| age > ROTATE_INTERVAL);
+        if too_big || too_old {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.file.write_all(b"\n")?;
+        self.size += len;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        shift_rotated(&self.retention)?;
+        prune_rotated(&self.retention);
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_path(0))?;
+        self.size = 0;
+        self.opened_at = SystemTime::now();
+        Ok(())
+    }
+}
+
+/// Move `server-output.log` to `.1.log`, `.1` to `.2`, ... dropping the oldest.
+fn shift_rotated(retention: &config::LogRetention) -> std::io::Result<()> {
+    let keep = retention.max_files.max(1);
+    let _ = fs::remove_file(log_path(keep));
+    for index in (0..keep).rev() {
+        let from = log_path(index);
+        if from.exists() {
+            fs::rename(&from, log_path(index + 1))?;
+        }
+    }
+    Ok(())
+}
+
+/// Delete rotated files past `max_files` or older than `max_age_days`.
+fn prune_rotated(retention: &config::LogRetention) {
+    let max_age = Duration::from_secs(retention.max_age_days * 24 * 60 * 60);
+    let Ok(entries) = fs::read_dir(logs_dir()) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(index) = name
+            .strip_prefix(&format!("{LOG_STEM}."))
+            .and_then(|rest| rest.strip_suffix(".log"))
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|m| m.elapsed().ok())
+            .is_some_and(|age| age > max_age);
+        if index > retention.max_files || expired {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Which child pipe a line came from.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "trace" => Some(LogLevel::Trace),
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "error" => Some(LogLevel::Error),
+            "fatal" => Some(LogLevel::Fatal),
+            _ => None,
+        }
+    }
+
+    /// Map pino's numeric levels (10 = trace ... 60 = fatal).
+    fn from_pino(level: u64) -> Self {
+        match level {
+            0..=10 => LogLevel::Trace,
+            11..=20 => LogLevel::Debug,
+            21..=30 => LogLevel::Info,
+            31..=40 => LogLevel::Warn,
+            41..=50 => LogLevel::Error,
+            _ => LogLevel::Fatal,
+        }
+    }
+}
+
+/// Payload of the `server-log` event: one parsed line of server output.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerLogLine {
+    pub level: LogLevel,
+    /// Taken from a `[Component]` message prefix or pino's `component` field.
+    pub component: Option<String>,
+    pub message: String,
+    /// Unix timestamp in milliseconds; the time the line was received if the
+    /// server didn't include a full timestamp.
+    pub timestamp: u64,
+    pub stream: OutputStream,
+}
+
+/// Remove ANSI color sequences (pino-pretty colorizes its output).
+pub(crate) fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' && chars.peek() == Some(&'[') {
+            chars.next();
+            // Skip parameters up to and including the final byte (0x40..=0x7e).
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Split a `[Component] message` prefix off a message.
+fn split_component(message: &str) -> (Option<String>, String) {
+    if let Some(rest) = message.strip_prefix('[') {
+        if let Some((name, msg)) = rest.split_once(']') {
+            if !name.is_empty() && !name.contains(' ') {
+                return (Some(name.to_string()), msg.trim_start().to_string());
+            }
+        }
+    }
+    (None, message.to_string())
+}
+
+/// Parse one line of server output. Understands pino JSON lines, pino-pretty's
+/// `[HH:MM:ss] LEVEL: message` format and plain `console.log` text.
+pub fn parse_line(line: &str, stream: OutputStream, received_at: u64) -> ServerLogLine {
+    let line = strip_ansi(line);
+    let default_level = match stream {
+        OutputStream::Stdout => LogLevel::Info,
+        OutputStream::Stderr => LogLevel::Error,
+    };
+
+    // pino JSON: {"level":30,"time":1700000000000,"msg":"..."}
+    if line.starts_with('{') {
+        if let Ok(serde_json::Value::Object(obj)) = serde_json::from_str(&line) {
+            let level = match &obj.get("level") {
+                Some(serde_json::Value::Number(n)) => n.as_u64().map(LogLevel::from_pino),
+                Some(serde_json::Value::String(s)) => LogLevel::parse(s),
+                _ => None,
+            }
+            .unwrap_or(default_level);
+            let msg = obj.get("msg").and_then(|m| m.as_str()).unwrap_or_default();
+            let (prefix, message) = split_component(msg);
+            let component = obj
+                .get("component")
+                .and_then(|c| c.as_str())
+                .map(|c| c.to_string())
+                .or(prefix);
+            return ServerLogLine {
+                level,
+                component,
+                message,
+                timestamp: obj
+                    .get("time")
+                    .and_then(|t| t.as_u64())
+                    .unwrap_or(received_at),
+                stream,
+            };
+        }
+    }
+
+    // pino-pretty: [12:34:56] INFO: message  /  [12:34:56.789] WARN (123): message
+    if let Some(rest) = line.strip_prefix('[') {
+        if let Some((time, rest)) = rest.split_once("] ") {
+            let is_time = !time.is_empty()
+                && time
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == ':' || c == '.');
+            if let Some((head, msg)) = rest.split_once(": ") {
+                let name = head.split_whitespace().next().unwrap_or_default();
+                if let (true, Some(level)) = (is_time, LogLevel::parse(name)) {
+                    let (component, message) = split_component(msg);
+                    return ServerLogLine {
+                        level,
+                        component,
+                        message,
+                        timestamp: received_at,
+                        stream,
+                    };
+                }
+            }
+        }
+    }
+
+    // Plain text, e.g. `console.log("[Server] ...")` before the logger is set
+    // up, or Bun's own `error: ...` messages.
+    let level = line
+        .split_once(':')
+        .and_then(|(prefix, _)| LogLevel::parse(prefix.trim()))
+        .unwrap_or(default_level);
+    let (component, message) = split_component(&line);
+    ServerLogLine {
+        level,
+        component,
+        message,
+        timestamp: received_at,
+        stream,
+    }
+}
+
+pub struct LogState {
+    writer: Mutex<Option<RotatingLog>>,
+    /// Minimum level forwarded as `server-log` events; `None` = no subscriber.
+    min_level: Mutex<Option<LogLevel>>,
+}
+
+impl LogState {
+    pub fn new() -> Self {
+        Self {
+            writer: Mutex::new(None),
+            min_level: Mutex::new(None),
+        }
+    }
+
+    fn min_level(&self) -> Option<LogLevel> {
+        self.min_level.lock().ok().and_then(|l| *l)
+    }
+
+    /// Append one line of server output, opening the log file on first use.
+    fn write_line(&self, line: &str) {
+        let Ok(mut lock) = self.writer.lock() else {
+            return;
+        };
+        if lock.is_none() {
+            match RotatingLog::open(config::load_config().logs) {
+                Ok(log) => *lock = Some(log),
+                Err(e) => {
+                    eprintln!("[logs] Failed to open server log: {e}");
+                    return;
+                }
+            }
+        }
+        if let Some(log) = lock.as_mut() {
+            if let Err(e) = log.write_line(line) {
+                eprintln!("[logs] Failed to write server log: {e}");
+                // Reopen on the next line in case the file was removed underneath us.
+                *lock = None;
+            }
+        }
+    }
+}
+
+/// Copy a child output stream into the server log, line by line, until it closes.
+/// Lines are also parsed and emitted as `server-log` events while subscribed.
+pub fn capture<R>(host: Host, reader: R, stream: OutputStream)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            let line = String::from_utf8_lossy(&buf);
+            let line = line.trim_end_matches(['\n', '\r']);
+            // Headless runs log to stdout; keep `tauri dev` output visible too.
+            if host.is_headless() {
+                println!("{line}");
+            } else if cfg!(debug_assertions) {
+                eprintln!("{line}");
+            }
+            let state = host.logs();
+            state.write_line(line);
+            crate::server::observe_output(&host, line);
+
+            if let Some(min_level) = state.min_level() {
+                let parsed = parse_line(line, stream, crate::server::now_ms());
+                if parsed.level >= min_level {
+                    host.emit("server-log", parsed);
+                }
+            }
+        }
+    });
+}
+
+/// A slice of the active server log file.
+#[derive(Serialize)]
+pub struct LogChunk {
+    pub path: String,
+    pub text: String,
+    /// Byte offset of `text` within the file.
+    pub start: u64,
+    /// Byte offset just past `text`; pass as `offset` to continue reading.
+    pub end: u64,
+    /// Current size of the file.
+    pub size: u64,
+}
+
+/// Offset where the last `lines` lines of the file begin.
+fn tail_start(file: &mut File, size: u64, lines: usize) -> std::io::Result<u64> {
+    const BLOCK: u64 = 64 * 1024;
+    if lines == 0 {
+        return Ok(size);
+    }
+    let mut pos = size;
+    let mut newlines = 0;
+    let mut buf = vec![0u8; BLOCK as usize];
+
+    // Skip the trailing newline of the last line.
+    let mut skip_last = true;
+    while pos > 0 {
+        let len = BLOCK.min(pos);
+        pos -= len;
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut buf[..len as usize])?;
+        for i in (0..len as usize).rev() {
+            if buf[i] != b'\n' {
+                skip_last = false;
+                continue;
+            }
+            if skip_last {
+                skip_last = false;
+                continue;
+            }
+            newlines += 1;
+            if newlines == lines {
+                return Ok(pos + i as u64 + 1);
+            }
+        }
+        if size - pos >= MAX_READ_BYTES {
+            break;
+        }
+    }
+    Ok(pos)
+}
+
+/// Read the server log: the last `lines` lines by default, or `length` bytes
+/// starting at `offset` when an offset is given.
+#[tauri::command]
+pub async fn get_server_logs(
+    lines: Option<usize>,
+    offset: Option<u64>,
+    length: Option<u64>,
+) -> Result<LogChunk, String> {
+    let path = log_path(0);
+    let mut file = match File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(LogChunk {
+                path: path.to_string_lossy().to_string(),
+                text: String::new(),
+                start: 0,
+                end: 0,
+                size: 0,
+            });
+        }
+        Err(e) => return Err(format!("Failed to open server log: {e}")),
+    };
+    let size = file.metadata().map_err(|e| e.to_string())?.len();
+
+    let start = match offset {
+        Some(offset) => offset.min(size),
+        None => tail_start(&mut file, size, lines.unwrap_or(DEFAULT_TAIL_LINES))
+            .map_err(|e| format!("Failed to read server log: {e}"))?,
+    };
+    let len = length
+        .unwrap_or(size - start)
+        .min(size - start)
+        .min(MAX_READ_BYTES);
+
+    let mut buf = vec![0u8; len as usize];
+    file.seek(SeekFrom::Start(start))
+        .and_then(|_| file.read_exact(&mut buf))
+        .map_err(|e| format!("Failed to read server log: {e}"))?;
+
+    Ok(LogChunk {
+        path: path.to_string_lossy().to_string(),
+        text: String::from_utf8_lossy(&buf).to_string(),
+        start,
+        end: start + len,
+        size,
+    })
+}
+
+/// Start (or re-filter) the live `server-log` event stream.
+/// `min_level` defaults to `info`.
+#[tauri::command]
+pub async fn subscribe_server_logs(
+    app: AppHandle,
+    min_level: Option<String>,
+) -> Result<(), String> {
+    let level = match min_level {
+        Some(name) => LogLevel::parse(&name).ok_or_else(|| format!("Unknown log level: {name}"))?,
+        None => LogLevel::Info,
+    };
+    let state = app.state::<LogState>();
+    *state.min_level.lock().map_err(|e| e.to_string())? = Some(level);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unsubscribe_server_logs(app: AppHandle) -> Result<(), String> {
+    let state = app.state::<LogState>();
+    *state.min_level.lock().map_err(|e| e.to_string())? = None;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pino_json() {
+        let line = r#"{"level":40,"time":1700000000000,"pid":4242,"hostname":"mac","component":"sessions","msg":"Session file missing"}"#;
+        let parsed = parse_line(line, OutputStream::Stdout, 1);
+        assert_eq!(parsed.level, LogLevel::Warn);
+        assert_eq!(parsed.component.as_deref(), Some("sessions"));
+        assert_eq!(parsed.message, "Session file missing");
+        assert_eq!(parsed.timestamp, 1700000000000);
+    }
+
+    #[test]
+    fn parses_pino_pretty_with_ansi() {
+        let line = "\u{1b}[90m[12:34:56.789]\u{1b}[39m \u{1b}[33mWARN\u{1b}[39m (4242): \u{1b}[36m[Watcher] Falling back to polling\u{1b}[39m";
+        let parsed = parse_line(line, OutputStream::Stdout, 1);
+        assert_eq!(parsed.level, LogLevel::Warn);
+        assert_eq!(parsed.component.as_deref(), Some("Watcher"));
+        assert_eq!(parsed.message, "Falling back to polling");
+        assert_eq!(parsed.timestamp, 1);
+    }
+
+    #[test]
+    fn parses_plain_console_output() {
+        let line = "[Server] Server running at http://127.0.0.1:3400";
+        let parsed = parse_line(line, OutputStream::Stdout, 1);
+        assert_eq!(parsed.level, LogLevel::Info);
+        assert_eq!(parsed.component.as_deref(), Some("Server"));
+        assert_eq!(parsed.message, "Server running at http://127.0.0.1:3400");
+    }
+
+    #[test]
+    fn parses_bun_error_on_stderr() {
+        let line = "error: Cannot find module \"yepanywhere\" from \"/tmp\"";
+        let parsed = parse_line(line, OutputStream::Stderr, 1);
+        assert_eq!(parsed.level, LogLevel::Error);
+        assert_eq!(parsed.component, None);
+        assert_eq!(parsed.message, line);
+    }
+}
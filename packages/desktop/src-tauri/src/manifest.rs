@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+/// What was asked for and what got installed for one component.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentRecord {
+    /// The requested version: exact, semver range, dist-tag or release tag.
+    pub spec: String,
+    /// The version that spec resolved to.
+    pub version: String,
+    /// Unix timestamp in milliseconds.
+    pub installed_at: u64,
+}
+
+/// Resolved versions of everything the installer put in the data dir, so a
+/// setup can be reproduced by pinning `versions` to them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Keyed by package name (`yepanywhere`, `@anthropic-ai/claude-code`) or `codex`.
+    #[serde(default)]
+    pub components: BTreeMap<String, ComponentRecord>,
+}
+
+pub fn manifest_path() -> PathBuf {
+    config::data_dir().join("components.json")
+}
+
+pub fn read() -> Manifest {
+    fs::read_to_string(manifest_path())
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Record a successful install, replacing any previous entry for `name`.
+pub fn record(name: &str, spec: &str, version: &str) {
+    let mut manifest = read();
+    manifest.components.insert(
+        name.to_string(),
+        ComponentRecord {
+            spec: spec.to_string(),
+            version: version.to_string(),
+            installed_at: crate::server::now_ms(),
+        },
+    );
+    let result = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| e.to_string())
+        .and_then(|json| fs::write(manifest_path(), json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        eprintln!("[installer] Failed to write component manifest: {e}");
+    }
+}
+
+/// The `version` field of the `package.json` in `package_dir`.
+pub fn package_version(package_dir: &Path) -> Option<String> {
+    let contents = fs::read_to_string(package_dir.join("package.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    json["version"].as_str().map(|v| v.to_string())
+}
+
+/// Version of an npm package installed in the data dir, from its `package.json`.
+pub fn installed_version(package: &str) -> Option<String> {
+    package_version(&config::data_dir().join("node_modules").join(package))
+}
+
+/// Versions of the installed components, as recorded at install time.
+#[tauri::command]
+pub async fn get_component_manifest() -> Result<Manifest, String> {
+    Ok(read())
+}
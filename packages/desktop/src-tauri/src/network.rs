@@ -0,0 +1,89 @@
+use serde::Serialize;
+use std::net::IpAddr;
+use tauri::{AppHandle, Manager};
+
+use crate::config::{self, BindAddress};
+use crate::server::ServerState;
+
+/// A non-loopback address of this machine, for LAN access from other devices.
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkAddress {
+    pub interface: String,
+    pub ip: String,
+    /// "ipv4" or "ipv6".
+    pub family: &'static str,
+    /// URL other devices can open, once the server has a port.
+    pub url: Option<String>,
+}
+
+/// Non-loopback, non-link-local addresses of every interface that is up.
+fn lan_interfaces() -> Vec<(String, IpAddr)> {
+    let Ok(interfaces) = if_addrs::get_if_addrs() else {
+        return vec![];
+    };
+    interfaces
+        .into_iter()
+        .filter(|iface| !iface.is_loopback() && !iface.is_link_local() && iface.is_oper_up())
+        .map(|iface| {
+            let ip = iface.ip();
+            (iface.name, ip)
+        })
+        .collect()
+}
+
+fn url_for(ip: IpAddr, port: u16) -> String {
+    match ip {
+        IpAddr::V4(v4) => format!("http://{v4}:{port}"),
+        IpAddr::V6(v6) => format!("http://[{v6}]:{port}"),
+    }
+}
+
+/// Host value to pass to the server for `bind`, or `None` for the server's
+/// default loopback-only binding.
+pub fn bind_host(bind: &BindAddress) -> Result<Option<String>, String> {
+    match bind {
+        BindAddress::Loopback => Ok(None),
+        BindAddress::AllInterfaces => Ok(Some("0.0.0.0".to_string())),
+        BindAddress::Interface { name } => {
+            let addrs: Vec<IpAddr> = lan_interfaces()
+                .into_iter()
+                .filter(|(iface, _)| iface == name)
+                .map(|(_, ip)| ip)
+                .collect();
+            // Prefer IPv4: it's what people type into a phone's browser.
+            addrs
+                .iter()
+                .find(|ip| ip.is_ipv4())
+                .or(addrs.first())
+                .map(|ip| Some(ip.to_string()))
+                .ok_or_else(|| format!("Network interface {name} has no usable address"))
+        }
+    }
+}
+
+/// List this machine's LAN addresses. `url` is set for the addresses the
+/// running server is actually reachable on, given the configured bind address.
+#[tauri::command]
+pub async fn list_network_addresses(app: AppHandle) -> Result<Vec<NetworkAddress>, String> {
+    let state = app.state::<ServerState>();
+    let port = *state.port.lock().map_err(|e| e.to_string())?;
+    let bind = config::load_config().bind_address;
+    let bound_host = bind_host(&bind).ok().flatten();
+
+    Ok(lan_interfaces()
+        .into_iter()
+        .map(|(interface, ip)| {
+            let exposed = match &bind {
+                BindAddress::Loopback => false,
+                BindAddress::AllInterfaces => ip.is_ipv4(),
+                BindAddress::Interface { .. } => bound_host.as_deref() == Some(&ip.to_string()),
+            };
+            NetworkAddress {
+                url: port.filter(|_| exposed).map(|port| url_for(ip, port)),
+                interface,
+                ip: ip.to_string(),
+                family: if ip.is_ipv4() { "ipv4" } else { "ipv6" },
+            }
+        })
+        .collect())
+}
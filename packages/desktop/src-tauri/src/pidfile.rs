@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::time::Duration;
+
+use crate::config;
+
+/// Record of the running server, so a later launch can find it if the app
+/// was force-quit before it could stop the server.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PidFile {
+    /// Server PID, which is also its process group ID.
+    pub pid: u32,
+    pub port: u16,
+    /// SHA-256 of the desktop auth token, to recognise the process without
+    /// persisting the token itself.
+    pub token_hash: String,
+    /// Substring of the server's command line used to confirm a live PID is
+    /// still our server and not an unrelated process that reused it.
+    pub command_marker: String,
+    /// Working directory of a dev-mode server, which a dev command like
+    /// `pnpm dev` doesn't identify on its own.
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+    /// Unix timestamp in milliseconds.
+    pub started_at: u64,
+    /// PID of the desktop process that started the server; 0 in records
+    /// written before this was tracked.
+    #[serde(default)]
+    pub owner_pid: u32,
+}
+
+pub fn pid_path() -> PathBuf {
+    config::data_dir().join("server.pid")
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+pub fn write(record: &PidFile) {
+    let result = serde_json::to_string_pretty(record)
+        .map_err(|e| e.to_string())
+        .and_then(|json| fs::write(pid_path(), json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        eprintln!("[server] Failed to write PID file: {e}");
+    }
+}
+
+#[cfg(unix)]
+pub fn read() -> Option<PidFile> {
+    let contents = fs::read_to_string(pid_path()).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+pub fn remove() {
+    let _ = fs::remove_file(pid_path());
+}
+
+/// How far a live process's start time may be from `started_at`: `ps`
+/// reports whole seconds, and the PID file is written just after the spawn.
+#[cfg(unix)]
+const START_TIME_TOLERANCE: Duration = Duration::from_secs(5);
+
+/// Whether the process recorded in `record` is still our server.
+#[cfg(unix)]
+fn is_our_server(record: &PidFile) -> bool {
+    let Some(command) = crate::process::command_line(record.pid) else {
+        return false;
+    };
+    if !command.contains(&record.command_marker) {
+        return false;
+    }
+    if let Some(cwd) = &record.cwd {
+        if crate::process::cwd(record.pid).as_ref() != Some(cwd) {
+            return false;
+        }
+    }
+    // Where the OS lets us read the environment, the token pins it down exactly.
+    // Elsewhere (macOS), a process that reused the PID won't share the start time.
+    match crate::process::environ_var(record.pid, "DESKTOP_AUTH_TOKEN") {
+        Some(token) => hash_token(&token) == record.token_hash,
+        None => crate::process::elapsed_ms(record.pid).is_some_and(|elapsed| {
+            let started_at = crate::server::now_ms().saturating_sub(elapsed);
+            started_at.abs_diff(record.started_at) <= START_TIME_TOLERANCE.as_millis() as u64
+        }),
+    }
+}
+
+/// Whether the desktop process that started the recorded server is still
+/// running, e.g. a `--headless` instance, which single-instance doesn't cover.
+#[cfg(unix)]
+fn owner_alive(record: &PidFile) -> bool {
+    if record.owner_pid == 0 || record.owner_pid == std::process::id() {
+        return false;
+    }
+    let Some(exe) = std::env::current_exe()
+        .ok()
+        .and_then(|path| Some(path.file_name()?.to_string_lossy().to_string()))
+    else {
+        return false;
+    };
+    crate::process::command_line(record.owner_pid).is_some_and(|command| command.contains(&exe))
+}
+
+/// Terminate a server left behind by a previous session, if the PID file
+/// points at one that is still running, and clear the PID file. Refuses if
+/// the desktop process that started the server is still alive.
+#[cfg(unix)]
+pub async fn reap_stale(drain: std::time::Duration) -> Result<(), String> {
+    let Some(record) = read() else {
+        return Ok(());
+    };
+    if owner_alive(&record) {
+        return Err(format!(
+            "Server is already running under another instance (pid {}, port {})",
+            record.owner_pid, record.port
+        ));
+    }
+    if is_our_server(&record) {
+        eprintln!(
+            "[server] Terminating stale server from a previous session (pid {}, port {})",
+            record.pid, record.port
+        );
+        let report = crate::process::terminate_group(record.pid, drain).await;
+        if !report.graceful {
+            eprintln!(
+                "[server] Force-killed stale processes {:?}",
+                report.forced_pids
+            );
+        }
+    }
+    remove();
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub async fn reap_stale(_drain: std::time::Duration) -> Result<(), String> {
+    remove();
+    Ok(())
+}
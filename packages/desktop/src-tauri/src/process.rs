@@ -0,0 +1,268 @@
+use serde::Serialize;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::time::Duration;
+#[cfg(unix)]
+use std::time::Instant;
+use tokio::process::Child;
+
+/// How often to check whether the process group has exited while draining.
+#[cfg(unix)]
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long to wait for the kernel to reap the child after SIGKILL.
+#[cfg(unix)]
+const KILL_REAP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Outcome of stopping the server's process group.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShutdownReport {
+    /// PID of the server child, if one was running.
+    pub pid: Option<u32>,
+    /// True if everything exited within the drain timeout after SIGTERM.
+    pub graceful: bool,
+    /// Processes still alive at the drain deadline that had to be SIGKILLed.
+    pub forced_pids: Vec<u32>,
+}
+
+/// PIDs of all processes in process group `pgid`.
+#[cfg(unix)]
+pub fn group_members(pgid: u32) -> Vec<u32> {
+    let Ok(output) = std::process::Command::new("ps")
+        .args(["-A", "-o", "pid=", "-o", "pgid="])
+        .output()
+    else {
+        return vec![];
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse::<u32>().ok()?;
+            let group = fields.next()?.parse::<u32>().ok()?;
+            (group == pgid).then_some(pid)
+        })
+        .collect()
+}
+
+/// Resource usage of one process, as reported by `ps`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    /// Resident set size in bytes.
+    pub rss_bytes: u64,
+    /// Cumulative CPU time (user + system) in milliseconds.
+    pub cpu_time_ms: u64,
+}
+
+/// Resource usage of every process in group `pgid`.
+#[cfg(unix)]
+pub fn group_usage(pgid: u32) -> Vec<ProcessUsage> {
+    let Ok(output) = std::process::Command::new("ps")
+        .args([
+            "-A", "-o", "pid=", "-o", "pgid=", "-o", "rss=", "-o", "time=",
+        ])
+        .output()
+    else {
+        return vec![];
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse::<u32>().ok()?;
+            let group = fields.next()?.parse::<u32>().ok()?;
+            if group != pgid {
+                return None;
+            }
+            let rss_kb = fields.next()?.parse::<u64>().ok()?;
+            let cpu_time_ms = parse_ps_duration(fields.next()?)?;
+            Some(ProcessUsage {
+                pid,
+                rss_bytes: rss_kb * 1024,
+                cpu_time_ms,
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+pub fn group_usage(_pgid: u32) -> Vec<ProcessUsage> {
+    vec![]
+}
+
+/// Parse a `ps` cumulative CPU time or elapsed time, in milliseconds:
+/// `[[dd-]hh:]mm:ss[.cc]`.
+#[cfg(unix)]
+fn parse_ps_duration(value: &str) -> Option<u64> {
+    let (days, clock) = match value.split_once('-') {
+        Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+        None => (0, value),
+    };
+    let (clock, fraction_ms) = match clock.split_once('.') {
+        Some((clock, fraction)) => {
+            // Fractions are hundredths on macOS; normalise to milliseconds.
+            let digits: String = fraction.chars().take(3).collect();
+            let scale = 10u64.pow(3 - digits.len() as u32);
+            (clock, digits.parse::<u64>().ok()? * scale)
+        }
+        None => (clock, 0),
+    };
+    let mut secs = 0u64;
+    for part in clock.split(':') {
+        secs = secs * 60 + part.parse::<u64>().ok()?;
+    }
+    Some(((days * 86_400 + secs) * 1000) + fraction_ms)
+}
+
+/// Whether any process in group `pgid` is still alive.
+#[cfg(unix)]
+fn group_alive(pgid: u32) -> bool {
+    // Signal 0 only checks for existence; ESRCH means the group is empty.
+    unsafe { libc::kill(-(pgid as i32), 0) == 0 }
+}
+
+/// Send `signal` to every process in group `pgid`.
+#[cfg(unix)]
+pub fn signal_group(pgid: u32, signal: i32) {
+    unsafe {
+        libc::kill(-(pgid as i32), signal);
+    }
+}
+
+/// Stop the child and everything in its process group: SIGTERM the group,
+/// wait up to `drain` for it to exit, then SIGKILL whatever is left.
+/// Relies on `process_group(0)` at spawn, so the child's PID (recorded at
+/// spawn, since `Child::id` is gone once the child is reaped) is the PGID.
+#[cfg(unix)]
+pub async fn shutdown(mut child: Child, pid: Option<u32>, drain: Duration) -> ShutdownReport {
+    let Some(pid) = pid.or(child.id()) else {
+        return ShutdownReport {
+            pid: None,
+            graceful: true,
+            forced_pids: vec![],
+        };
+    };
+    stop_group(pid, Some(&mut child), drain).await
+}
+
+/// Stop a process group we didn't spawn in this session (e.g. a server left
+/// behind by a force-quit), with the same SIGTERM/drain/SIGKILL sequence.
+#[cfg(unix)]
+pub async fn terminate_group(pgid: u32, drain: Duration) -> ShutdownReport {
+    stop_group(pgid, None, drain).await
+}
+
+#[cfg(unix)]
+async fn stop_group(pgid: u32, mut child: Option<&mut Child>, drain: Duration) -> ShutdownReport {
+    signal_group(pgid, libc::SIGTERM);
+
+    let deadline = Instant::now() + drain;
+    loop {
+        // Reap the server itself so its zombie doesn't keep the group "alive".
+        if let Some(child) = child.as_mut() {
+            let _ = child.try_wait();
+        }
+        if !group_alive(pgid) {
+            return ShutdownReport {
+                pid: Some(pgid),
+                graceful: true,
+                forced_pids: vec![],
+            };
+        }
+        if Instant::now() >= deadline {
+            break;
+        }
+        tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+    }
+
+    let forced_pids = group_members(pgid);
+    eprintln!(
+        "[server] Process group {pgid} did not exit within {}s, sending SIGKILL to {forced_pids:?}",
+        drain.as_secs()
+    );
+    signal_group(pgid, libc::SIGKILL);
+    if let Some(child) = child {
+        let _ = tokio::time::timeout(KILL_REAP_TIMEOUT, child.wait()).await;
+    }
+
+    ShutdownReport {
+        pid: Some(pgid),
+        graceful: false,
+        forced_pids,
+    }
+}
+
+/// Full command line of `pid`, or `None` if it isn't running.
+#[cfg(unix)]
+pub fn command_line(pid: u32) -> Option<String> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "command=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let command = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !command.is_empty()).then_some(command)
+}
+
+/// Milliseconds since process `pid` started, to whole seconds.
+#[cfg(unix)]
+pub fn elapsed_ms(pid: u32) -> Option<u64> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "etime=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_ps_duration(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+/// Working directory of process `pid`, from `/proc` on Linux or `lsof` elsewhere.
+#[cfg(unix)]
+pub fn cwd(pid: u32) -> Option<PathBuf> {
+    if let Ok(path) = std::fs::read_link(format!("/proc/{pid}/cwd")) {
+        return Some(path);
+    }
+    let output = std::process::Command::new("lsof")
+        .args(["-a", "-p", &pid.to_string(), "-d", "cwd", "-Fn"])
+        .output()
+        .ok()?;
+    // `-Fn` prints fields one per line, the path prefixed with `n`.
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix('n'))
+        .map(PathBuf::from)
+}
+
+/// Value of environment variable `name` in another process, where the OS
+/// exposes it (Linux `/proc`). `None` if unavailable or unset.
+#[cfg(unix)]
+pub fn environ_var(pid: u32, name: &str) -> Option<String> {
+    let environ = std::fs::read(format!("/proc/{pid}/environ")).ok()?;
+    let prefix = format!("{name}=");
+    environ
+        .split(|b| *b == 0)
+        .map(String::from_utf8_lossy)
+        .find_map(|entry| entry.strip_prefix(&prefix).map(|v| v.to_string()))
+}
+
+#[cfg(not(unix))]
+pub async fn shutdown(mut child: Child, pid: Option<u32>, _drain: Duration) -> ShutdownReport {
+    // No process groups or SIGTERM here; kill the child directly.
+    let pid = pid.or(child.id());
+    let _ = child.kill().await;
+    ShutdownReport {
+        pid,
+        graceful: false,
+        forced_pids: pid.into_iter().collect(),
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn terminate_group(pgid: u32, _drain: Duration) -> ShutdownReport {
+    // No process groups here; the exited child was all there was.
+    ShutdownReport {
+        pid: Some(pgid),
+        graceful: true,
+        forced_pids: vec![],
+    }
+}
@@ -44,30 +44,21 @@ pub async fn spawn_pty(app: AppHandle, command: String, args: Vec<String>) -> Re
         })
         .map_err(|e| format!("Failed to open PTY: {e}"))?;
 
-    // Resolve command to use the bundled bun sidecar as the runtime.
-    // We can't rely on system node/bun — the app must work on a fresh macOS install.
-    let bun = {
-        let exe = std::env::current_exe()
-            .map_err(|e| format!("Could not resolve executable: {e}"))?;
-        let exe_dir = exe
-            .parent()
-            .ok_or_else(|| "Could not resolve executable directory".to_string())?;
-        let bin_name = if cfg!(windows) { "bun.exe" } else { "bun" };
-        exe_dir.join(bin_name)
-    };
-
     let data_dir = config::data_dir();
 
     // For known commands (claude, codex), resolve to their actual scripts/binaries
-    // and run them with the bundled bun. For unknown commands, run directly.
+    // and run them with the runtime. For unknown commands, run directly.
     let cmd = match command.as_str() {
         "claude" => {
+            // Run JS agents with the resolved runtime (bundled sidecar first),
+            // since a fresh macOS install has no system node/bun.
+            let runtime = crate::runtime::resolve().map_err(|e| e.to_string())?;
             let script = data_dir
                 .join("node_modules")
                 .join("@anthropic-ai")
                 .join("claude-code")
                 .join("cli.js");
-            let mut c = CommandBuilder::new(&bun);
+            let mut c = CommandBuilder::new(&runtime.path);
             c.arg(&script);
             for arg in &args {
                 c.arg(arg);
@@ -0,0 +1,143 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::config;
+use crate::host::Host;
+use crate::process::{self, ProcessUsage};
+use crate::server;
+
+/// CPU and memory of the server's whole process group at one point in time.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerResources {
+    /// Server PID, which is also its process group ID.
+    pub pid: u32,
+    /// Resident memory summed over the group, in bytes.
+    pub rss_bytes: u64,
+    /// CPU use of the group since the previous sample, where 100 is one full
+    /// core. None for the first sample after a (re)start.
+    pub cpu_percent: Option<f64>,
+    pub processes: Vec<ProcessUsage>,
+    /// Unix timestamp in milliseconds.
+    pub sampled_at: u64,
+}
+
+/// Payload of `server-memory-limit`, emitted just before a memory restart.
+#[derive(Debug, Clone, Serialize)]
+struct MemoryLimitExceeded {
+    rss_bytes: u64,
+    limit_bytes: u64,
+}
+
+/// Previous CPU reading, so each sample can report usage over the interval.
+struct CpuBaseline {
+    pid: u32,
+    cpu_time_ms: u64,
+    at: Instant,
+}
+
+pub struct ResourceState {
+    baseline: Mutex<Option<CpuBaseline>>,
+}
+
+impl ResourceState {
+    pub fn new() -> Self {
+        Self {
+            baseline: Mutex::new(None),
+        }
+    }
+}
+
+/// Sample the managed server's process group, or `None` if it isn't running.
+async fn sample(host: &Host) -> Option<ServerResources> {
+    let pid = (*host.server().pid.lock().ok()?)?;
+    // `group_usage` waits on `ps`; keep that off the async runtime.
+    let processes = tokio::task::spawn_blocking(move || process::group_usage(pid))
+        .await
+        .ok()?;
+    if processes.is_empty() {
+        return None;
+    }
+    let rss_bytes = processes.iter().map(|p| p.rss_bytes).sum();
+    let cpu_time_ms: u64 = processes.iter().map(|p| p.cpu_time_ms).sum();
+
+    let now = Instant::now();
+    let mut baseline = host.resources().baseline.lock().ok()?;
+    let cpu_percent = baseline
+        .as_ref()
+        .filter(|prev| prev.pid == pid)
+        .and_then(|prev| {
+            let wall_ms = now.duration_since(prev.at).as_millis() as f64;
+            // Children that exit take their CPU time with them, so the
+            // total can drop; report that interval as idle.
+            let used_ms = cpu_time_ms.saturating_sub(prev.cpu_time_ms) as f64;
+            (wall_ms > 0.0).then(|| used_ms / wall_ms * 100.0)
+        });
+    *baseline = Some(CpuBaseline {
+        pid,
+        cpu_time_ms,
+        at: now,
+    });
+
+    Some(ServerResources {
+        pid,
+        rss_bytes,
+        cpu_percent,
+        processes,
+        sampled_at: server::now_ms(),
+    })
+}
+
+/// Sample the server every `sample_interval_secs`, emit `server-resources`,
+/// and restart it gracefully once it stays over the configured memory limit.
+/// Runs for the lifetime of the app.
+pub async fn monitor(host: Host) {
+    let mut over_limit = 0u32;
+    loop {
+        let policy = config::load_config().resources;
+        tokio::time::sleep(Duration::from_secs(policy.sample_interval_secs.max(1))).await;
+
+        let Some(resources) = sample(&host).await else {
+            over_limit = 0;
+            continue;
+        };
+        host.emit("server-resources", &resources);
+
+        let Some(limit_mb) = policy.memory_limit_mb else {
+            over_limit = 0;
+            continue;
+        };
+        let limit_bytes = limit_mb * 1024 * 1024;
+        if resources.rss_bytes <= limit_bytes {
+            over_limit = 0;
+            continue;
+        }
+        over_limit += 1;
+        if over_limit < policy.memory_limit_samples.max(1) {
+            continue;
+        }
+
+        over_limit = 0;
+        eprintln!(
+            "[server] Memory use {} MiB exceeds limit of {limit_mb} MiB, restarting",
+            resources.rss_bytes / (1024 * 1024)
+        );
+        host.emit(
+            "server-memory-limit",
+            MemoryLimitExceeded {
+                rss_bytes: resources.rss_bytes,
+                limit_bytes,
+            },
+        );
+        if let Err(e) = server::restart(&host).await {
+            eprintln!("[server] Memory-limit restart failed: {e}");
+        }
+    }
+}
+
+/// Current CPU and memory of the managed server, or null if it isn't running.
+#[tauri::command]
+pub async fn get_server_resources(app: AppHandle) -> Result<Option<ServerResources>, String> {
+    Ok(sample(&Host::from(&app)).await)
+}
@@ -0,0 +1,235 @@
+use semver::Version;
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::config;
+
+/// Oldest runtimes the server and agent CLIs are known to work on.
+const MIN_BUN_VERSION: Version = Version::new(1, 1, 0);
+const MIN_NODE_VERSION: Version = Version::new(20, 0, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeKind {
+    Bun,
+    Node,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeSource {
+    /// The Bun binary bundled next to the app executable.
+    Sidecar,
+    /// `runtime_path` in the app config.
+    Configured,
+    /// Found on the system PATH.
+    Path,
+}
+
+/// The JS runtime used to run the server, installs and agent CLIs.
+#[derive(Debug, Clone, Serialize)]
+pub struct Runtime {
+    pub kind: RuntimeKind,
+    pub path: PathBuf,
+    pub version: String,
+    pub source: RuntimeSource,
+}
+
+/// A candidate that was considered and rejected.
+#[derive(Debug, Clone, Serialize)]
+pub struct TriedLocation {
+    pub location: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeError {
+    pub tried: Vec<TriedLocation>,
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("No usable JavaScript runtime (Bun or Node) found. Tried:")?;
+        for tried in &self.tried {
+            write!(f, "\n  {}: {}", tried.location, tried.reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl Runtime {
+    /// Command that runs `script` with this runtime.
+    pub fn script_command(&self, script: &Path) -> tokio::process::Command {
+        let mut cmd = tokio::process::Command::new(&self.path);
+        cmd.arg(script);
+        cmd
+    }
+
+    /// Command that installs packages into the current directory:
+    /// `bun install` or, for Node, the `npm` that ships alongside it.
+    pub fn install_command(&self) -> tokio::process::Command {
+        let mut cmd = match self.kind {
+            RuntimeKind::Bun => tokio::process::Command::new(&self.path),
+            RuntimeKind::Node => {
+                let npm = if cfg!(windows) { "npm.cmd" } else { "npm" };
+                let sibling = self.path.with_file_name(npm);
+                tokio::process::Command::new(if sibling.exists() {
+                    sibling
+                } else {
+                    PathBuf::from(npm)
+                })
+            }
+        };
+        cmd.arg("install");
+        cmd
+    }
+}
+
+fn exe_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Tauri places externalBin sidecars next to the main executable (Contents/MacOS/).
+fn sidecar_path() -> Result<PathBuf, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Could not resolve executable: {e}"))?;
+    let exe_dir = exe
+        .parent()
+        .ok_or_else(|| "Could not resolve executable directory".to_string())?;
+    Ok(exe_dir.join(exe_name("bun")))
+}
+
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(exe_name(name)))
+        .find(|path| path.is_file())
+}
+
+fn kind_of(path: &Path) -> RuntimeKind {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if stem.starts_with("node") {
+        RuntimeKind::Node
+    } else {
+        RuntimeKind::Bun
+    }
+}
+
+/// Run `<path> --version` and check it against the minimum for `kind`.
+fn check_version(path: &Path, kind: RuntimeKind) -> Result<String, String> {
+    if !path.is_file() {
+        return Err("not found".to_string());
+    }
+    let output = std::process::Command::new(path)
+        .arg("--version")
+        .output()
+        .map_err(|e| format!("failed to run: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("`--version` exited with {}", output.status));
+    }
+    // Bun prints `1.2.17`, Node `v20.11.0`.
+    let raw = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let version = Version::parse(raw.trim_start_matches('v'))
+        .map_err(|_| format!("unrecognised version output {raw:?}"))?;
+    let min = match kind {
+        RuntimeKind::Bun => MIN_BUN_VERSION,
+        RuntimeKind::Node => MIN_NODE_VERSION,
+    };
+    if version < min {
+        return Err(format!(
+            "version {version} is older than the required {min}"
+        ));
+    }
+    Ok(version.to_string())
+}
+
+/// Last successful resolution, keyed by the configured path it was made with.
+static CACHE: Mutex<Option<(Option<String>, Runtime)>> = Mutex::new(None);
+
+/// Resolve the JS runtime: the bundled sidecar, then `runtime_path` from the
+/// config, then `bun` and `node` on PATH. The first one that runs and is
+/// recent enough wins; the result is cached until the config changes or the
+/// binary disappears.
+pub fn resolve() -> Result<Runtime, RuntimeError> {
+    let configured = config::load_config().runtime_path;
+    if let Ok(cache) = CACHE.lock() {
+        if let Some((key, runtime)) = cache.as_ref() {
+            if *key == configured && runtime.path.is_file() {
+                return Ok(runtime.clone());
+            }
+        }
+    }
+
+    let mut candidates: Vec<(String, Option<PathBuf>, RuntimeSource)> = vec![];
+    match sidecar_path() {
+        Ok(path) => candidates.push((
+            "bundled sidecar".to_string(),
+            Some(path),
+            RuntimeSource::Sidecar,
+        )),
+        Err(e) => candidates.push((
+            format!("bundled sidecar ({e})"),
+            None,
+            RuntimeSource::Sidecar,
+        )),
+    }
+    if let Some(path) = &configured {
+        candidates.push((
+            "runtime_path".to_string(),
+            Some(PathBuf::from(path)),
+            RuntimeSource::Configured,
+        ));
+    }
+    for name in ["bun", "node"] {
+        candidates.push((
+            format!("`{name}` on PATH"),
+            find_on_path(name),
+            RuntimeSource::Path,
+        ));
+    }
+
+    let mut tried = vec![];
+    for (label, path, source) in candidates {
+        let Some(path) = path else {
+            tried.push(TriedLocation {
+                location: label,
+                reason: "not found".to_string(),
+            });
+            continue;
+        };
+        let kind = kind_of(&path);
+        match check_version(&path, kind) {
+            Ok(version) => {
+                let runtime = Runtime {
+                    kind,
+                    path,
+                    version,
+                    source,
+                };
+                if let Ok(mut cache) = CACHE.lock() {
+                    *cache = Some((configured, runtime.clone()));
+                }
+                return Ok(runtime);
+            }
+            Err(reason) => tried.push(TriedLocation {
+                location: format!("{label} ({})", path.display()),
+                reason,
+            }),
+        }
+    }
+    Err(RuntimeError { tried })
+}
+
+/// The runtime the app will use, for display in settings and diagnostics.
+#[tauri::command]
+pub async fn get_runtime() -> Result<Runtime, String> {
+    resolve().map_err(|e| e.to_string())
+}
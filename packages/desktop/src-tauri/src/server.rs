@@ -1,15 +1,43 @@
 use rand::Rng;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Manager};
 use tokio::process::{Child, Command};
 
-use crate::config;
+use crate::config::{self, AppConfig, ServerMode};
+use crate::host::Host;
+use crate::process::{self, ShutdownReport};
+use crate::runtime::{self, RuntimeError, TriedLocation};
+use crate::{compat, health, logs, manifest, network, pidfile};
 
 pub struct ServerState {
     pub child: Mutex<Option<Child>>,
     pub desktop_token: Mutex<Option<String>>,
     /// The port the server is actually running on (auto-picked or user-specified).
     pub port: Mutex<Option<u16>>,
+    /// PID of the server child, which is also its process group ID.
+    pub pid: Mutex<Option<u32>>,
+    /// Most recent crashes, oldest first, capped at `MAX_CRASH_RECORDS`.
+    pub crashes: Mutex<VecDeque<CrashRecord>>,
+    /// Bumped on every spawn and deliberate stop, so a supervisor can tell
+    /// whether the child it watches is still the current one.
+    generation: AtomicU64,
+    /// Set when the current child printed a bind error (port already in use).
+    bind_failed: AtomicBool,
+    pub status: Mutex<ServerStatus>,
+    /// Where the current child was asked to listen and where it says it is.
+    pub listen: Mutex<ListenInfo>,
+    /// Held while a server is being spawned and brought up, so overlapping
+    /// starts (tray, CLI, dev watcher, watchdog, supervisor) can't both spawn
+    /// and overwrite `child`. Stopping doesn't take it, so it can cancel a start.
+    lifecycle: tokio::sync::Mutex<()>,
 }
 
 impl ServerState {
@@ -18,29 +46,400 @@ impl ServerState {
             child: Mutex::new(None),
             desktop_token: Mutex::new(None),
             port: Mutex::new(None),
+            pid: Mutex::new(None),
+            crashes: Mutex::new(VecDeque::new()),
+            generation: AtomicU64::new(0),
+            bind_failed: AtomicBool::new(false),
+            status: Mutex::new(ServerStatus::Stopped),
+            listen: Mutex::new(ListenInfo::default()),
+            lifecycle: tokio::sync::Mutex::new(()),
         }
     }
 
-    /// Synchronously kill the server process and its entire process group.
-    /// Called during app exit when the async runtime may not be available.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn next_generation(&self) -> u64 {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Store a crash and return how many crashes happened within the window.
+    fn record_crash(&self, crash: CrashRecord, window_secs: u64) -> u32 {
+        let Ok(mut crashes) = self.crashes.lock() else {
+            return 0;
+        };
+        if crashes.len() >= MAX_CRASH_RECORDS {
+            crashes.pop_front();
+        }
+        crashes.push_back(crash);
+        let since = now_ms().saturating_sub(window_secs * 1000);
+        crashes.iter().filter(|c| c.at >= since).count() as u32
+    }
+
+    /// Forget the current child's pid, token, port and listen info, returning
+    /// the pid. Call with `child` locked so a concurrent spawn can't interleave.
+    fn clear_child_info(&self) -> Option<u32> {
+        let pid = self.pid.lock().ok().and_then(|mut p| p.take());
+        if let Ok(mut token_lock) = self.desktop_token.lock() {
+            *token_lock = None;
+        }
+        if let Ok(mut port_lock) = self.port.lock() {
+            *port_lock = None;
+        }
+        if let Ok(mut listen) = self.listen.lock() {
+            *listen = ListenInfo::default();
+        }
+        pid
+    }
+
+    /// Take the server out of the state and stop its entire process group,
+    /// escalating to SIGKILL after the configured drain timeout.
+    pub async fn shutdown(&self) -> ShutdownReport {
+        // Bumping the generation tells the supervisor this exit is deliberate.
+        let (child, pid) = {
+            let Ok(mut child_lock) = self.child.lock() else {
+                return ShutdownReport::default();
+            };
+            self.next_generation();
+            (child_lock.take(), self.clear_child_info())
+        };
+        pidfile::remove();
+
+        let drain = Duration::from_secs(config::load_config().shutdown_timeout_secs);
+        match (child, pid) {
+            (Some(child), pid) => process::shutdown(child, pid, drain).await,
+            // The server itself already exited, but its group may not have.
+            (None, Some(pid)) => process::terminate_group(pid, drain).await,
+            (None, None) => ShutdownReport::default(),
+        }
+    }
+
+    /// Blocking variant of `shutdown` for app exit, where we can't await.
     pub fn kill_sync(&self) {
-        if let Ok(mut lock) = self.child.lock() {
-            if let Some(ref mut child) = *lock {
-                if let Some(pid) = child.id() {
-                    #[cfg(unix)]
-                    unsafe {
-                        // Kill the entire process group (negative PID = PGID).
-                        // Works because we set process_group(0) on spawn.
-                        libc::kill(-(pid as i32), libc::SIGTERM);
-                    }
-                    #[cfg(not(unix))]
-                    {
-                        let _ = child.start_kill();
-                    }
+        tauri::async_runtime::block_on(self.shutdown());
+    }
+}
+
+/// A socket the server reported listening on.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListenAddress {
+    pub host: String,
+    pub port: u16,
+}
+
+/// Requested versus actual listening addresses of the current child.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListenInfo {
+    pub requested_host: Option<String>,
+    pub requested_port: Option<u16>,
+    /// The loopback socket, from "Server running at <url>".
+    pub local: Option<ListenAddress>,
+    /// The LAN socket when a bind address is configured, from
+    /// "[NetworkBinding] Network socket listening on <host>:<port>".
+    pub network: Option<ListenAddress>,
+}
+
+/// Emitted as `server-listen-mismatch` when the server ends up listening
+/// somewhere other than where it was asked to.
+#[derive(Debug, Clone, Serialize)]
+pub struct ListenMismatch {
+    pub requested_host: Option<String>,
+    pub requested_port: Option<u16>,
+    pub actual: ListenAddress,
+}
+
+/// Lifecycle of the managed server, broadcast as `server-status-changed`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ServerStatus {
+    Stopped,
+    Starting,
+    Ready {
+        port: u16,
+        pid: Option<u32>,
+        /// Unix timestamp in milliseconds.
+        since: u64,
+    },
+    Stopping,
+    Crashed {
+        exit_code: Option<i32>,
+        signal: Option<i32>,
+        /// Unix timestamp in milliseconds.
+        at: u64,
+    },
+    /// The server package isn't installed; the setup wizard has to run first.
+    NeedsSetup,
+    /// The installed server version is outside the range this shell supports.
+    Incompatible {
+        installed: Option<String>,
+        supported: String,
+        upgrade_available: bool,
+    },
+    /// An external server isn't answering `/health`.
+    Unreachable {
+        url: String,
+        /// Unix timestamp in milliseconds.
+        since: u64,
+    },
+}
+
+impl fmt::Display for ServerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerStatus::Stopped => f.write_str("Server stopped"),
+            ServerStatus::Starting => f.write_str("Server starting"),
+            ServerStatus::Ready { port, .. } => write!(f, "Server running on port {port}"),
+            ServerStatus::Stopping => f.write_str("Server stopping"),
+            ServerStatus::Crashed {
+                exit_code: Some(code),
+                ..
+            } => write!(f, "Server crashed (exit code {code})"),
+            ServerStatus::Crashed {
+                signal: Some(signal),
+                ..
+            } => write!(f, "Server crashed (signal {signal})"),
+            ServerStatus::Crashed { .. } => f.write_str("Server crashed"),
+            ServerStatus::NeedsSetup => f.write_str("Setup required"),
+            ServerStatus::Incompatible { .. } => f.write_str("Server version not supported"),
+            ServerStatus::Unreachable { url, .. } => write!(f, "Server at {url} is unreachable"),
+        }
+    }
+}
+
+/// Record a status transition, update the tray and notify the frontend.
+pub(crate) fn set_status(host: &Host, status: ServerStatus) {
+    let state = host.server();
+    if let Ok(mut lock) = state.status.lock() {
+        if *lock == status {
+            return;
+        }
+        *lock = status.clone();
+    }
+    match host {
+        Host::App(app) => crate::tray::set_status(app, &status.to_string()),
+        Host::Headless(_) => println!("[server] {status}"),
+    }
+    host.emit("server-status-changed", &status);
+}
+
+/// Exit status and time of a server crash.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashRecord {
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    /// Unix timestamp in milliseconds.
+    pub at: u64,
+    pub message: String,
+}
+
+impl CrashRecord {
+    /// A failed launch, recorded like a crash so the restart policy counts it.
+    fn failed_launch(err: &StartupError, context: &str) -> Self {
+        let (exit_code, signal) = match *err {
+            StartupError::Exited { exit_code, signal } => (exit_code, signal),
+            _ => (None, None),
+        };
+        CrashRecord {
+            exit_code,
+            signal,
+            at: now_ms(),
+            message: format!("{context}: {err}"),
+        }
+    }
+}
+
+/// Payload of the `server-crashed` event.
+#[derive(Clone, Serialize)]
+struct ServerCrashed {
+    crash: CrashRecord,
+    restarting: bool,
+    restart_in_ms: Option<u64>,
+    /// True when the supervisor gave up because the server keeps crashing.
+    crash_loop: bool,
+}
+
+const MAX_CRASH_RECORDS: usize = 20;
+/// How often the supervisor checks whether the child is still alive.
+const SUPERVISOR_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Payload of the `server-port` event: the port the server finally bound.
+#[derive(Clone, Serialize)]
+struct ServerPort {
+    port: u16,
+    /// How many spawn attempts it took.
+    attempts: u32,
+}
+
+/// Payload of the `server-ready` event, emitted once `/health` answers.
+#[derive(Clone, Serialize)]
+struct ServerReady {
+    port: u16,
+    version: Option<String>,
+}
+
+/// Why a freshly spawned server never became ready.
+/// Emitted as the `server-startup-failed` event payload.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StartupError {
+    /// The child could not be spawned at all.
+    Spawn { message: String },
+    /// The child exited before `/health` answered.
+    Exited {
+        exit_code: Option<i32>,
+        signal: Option<i32>,
+    },
+    /// `/health` did not answer within the configured timeout.
+    Timeout { secs: u64 },
+    /// The server reported that its port was already taken.
+    AddrInUse { port: u16 },
+    /// The installed server version is outside `compat::SUPPORTED_SERVER_VERSIONS`.
+    Incompatible {
+        installed: Option<String>,
+        supported: String,
+        upgrade_available: bool,
+    },
+    /// Neither the sidecar nor a configured or system Bun/Node could be used.
+    NoRuntime { tried: Vec<TriedLocation> },
+    /// The server was stopped or replaced while it was starting.
+    Cancelled,
+}
+
+impl fmt::Display for StartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartupError::Spawn { message } => f.write_str(message),
+            StartupError::Exited {
+                exit_code: Some(code),
+                ..
+            } => write!(f, "Server exited during startup with code {code}"),
+            StartupError::Exited {
+                signal: Some(signal),
+                ..
+            } => write!(f, "Server was killed by signal {signal} during startup"),
+            StartupError::Exited { .. } => write!(f, "Server exited during startup"),
+            StartupError::Timeout { secs } => {
+                write!(f, "Server did not respond to /health within {secs}s")
+            }
+            StartupError::AddrInUse { port } => write!(f, "Port {port} is already in use"),
+            StartupError::Incompatible {
+                installed,
+                supported,
+                ..
+            } => write!(
+                f,
+                "Installed server version {} is not supported by this app (requires {supported})",
+                installed.as_deref().unwrap_or("unknown")
+            ),
+            StartupError::NoRuntime { tried } => RuntimeError {
+                tried: tried.clone(),
+            }
+            .fmt(f),
+            StartupError::Cancelled => f.write_str("Server was stopped during startup"),
+        }
+    }
+}
+
+impl StartupError {
+    fn spawn(err: impl ToString) -> Self {
+        StartupError::Spawn {
+            message: err.to_string(),
+        }
+    }
+
+    /// Whether the server ran and then failed, as a crash would, rather than
+    /// never being runnable at all. Only the former is worth retrying.
+    fn is_crash(&self) -> bool {
+        matches!(
+            self,
+            StartupError::Exited { .. }
+                | StartupError::Timeout { .. }
+                | StartupError::AddrInUse { .. }
+        )
+    }
+}
+
+/// Interval between `/health` polls while waiting for the server to come up.
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Split an exit status into (exit code, terminating signal).
+fn exit_parts(status: &ExitStatus) -> (Option<i32>, Option<i32>) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        (status.code(), status.signal())
+    }
+    #[cfg(not(unix))]
+    {
+        (status.code(), None)
+    }
+}
+
+/// Poll the server's `/health` route until it answers, the child exits, or the timeout elapses.
+async fn wait_for_health(
+    state: &ServerState,
+    port: u16,
+    timeout: Duration,
+) -> Result<(), StartupError> {
+    let client = health::client();
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        // Follow the server if its output says it ended up on another port.
+        let listening = state.port.lock().ok().and_then(|p| *p).unwrap_or(port);
+        let base_url = format!("http://127.0.0.1:{listening}");
+
+        // Bail out early if the child already died (e.g. bad install, port in use).
+        let exited = match state.child.lock() {
+            Ok(mut lock) => match lock.as_mut().map(|c| c.try_wait()) {
+                Some(Ok(Some(status))) => {
+                    *lock = None;
+                    Some(exit_parts(&status))
                 }
+                // Stopped while we were waiting.
+                None => Some((None, None)),
+                _ => None,
+            },
+            Err(_) => None,
+        };
+        if let Some((exit_code, signal)) = exited {
+            // Give the output readers a moment to see a bind error printed just before exit.
+            tokio::time::sleep(HEALTH_POLL_INTERVAL).await;
+            if state.bind_failed.load(Ordering::SeqCst) {
+                return Err(StartupError::AddrInUse { port });
             }
-            *lock = None;
+            return Err(StartupError::Exited { exit_code, signal });
+        }
+        if state.bind_failed.load(Ordering::SeqCst) {
+            return Err(StartupError::AddrInUse { port });
+        }
+
+        if health::is_healthy(&client, &base_url).await {
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            return Err(StartupError::Timeout {
+                secs: timeout.as_secs(),
+            });
         }
+        tokio::time::sleep(HEALTH_POLL_INTERVAL).await;
+    }
+}
+
+/// Read the `version` field of the server package that `start_server` will launch.
+pub(crate) fn server_version() -> Option<String> {
+    match config::dev_dir() {
+        Some(dev_dir) => manifest::package_version(&dev_dir),
+        None => manifest::installed_version("yepanywhere"),
     }
 }
 
@@ -51,21 +450,6 @@ fn generate_token() -> String {
     bytes.iter().map(|b| format!("{b:02x}")).collect()
 }
 
-/// Resolve the bundled Bun sidecar binary path.
-/// Tauri places externalBin sidecars next to the main executable (Contents/MacOS/).
-fn bun_path(_app: &AppHandle) -> Result<std::path::PathBuf, String> {
-    let exe = std::env::current_exe().map_err(|e| format!("Could not resolve executable: {e}"))?;
-    let exe_dir = exe
-        .parent()
-        .ok_or_else(|| "Could not resolve executable directory".to_string())?;
-    let bin_name = if cfg!(windows) { "bun.exe" } else { "bun" };
-    let path = exe_dir.join(bin_name);
-    if path.exists() {
-        return Ok(path);
-    }
-    Err(format!("Bun sidecar not found at {}", path.display()))
-}
-
 /// Find the yep server entry point.
 fn server_entry() -> Result<std::path::PathBuf, String> {
     let installed = config::data_dir()
@@ -80,9 +464,79 @@ fn server_entry() -> Result<std::path::PathBuf, String> {
     Err("Yep Anywhere server not found. Run setup first.".to_string())
 }
 
+/// Env vars the shell itself manages; user-configured values for these are ignored.
+const RESERVED_ENV: [&str; 3] = ["PORT", "YEP_ANYWHERE_DATA_DIR", "DESKTOP_AUTH_TOKEN"];
+
+/// Locations of agents the wizard installed, exported so the server uses
+/// them. Only set for agents that are actually installed. Claude Code is the
+/// entry script, which the server's SDK runs with its own JS runtime.
+fn agent_env() -> Vec<(&'static str, PathBuf)> {
+    let codex = if cfg!(windows) { "codex.exe" } else { "codex" };
+    let claude = config::data_dir()
+        .join("node_modules")
+        .join("@anthropic-ai")
+        .join("claude-code")
+        .join("cli.js");
+    [
+        ("YEP_ANYWHERE_CODEX_PATH", config::bin_dir().join(codex)),
+        ("YEP_ANYWHERE_CLAUDE_PATH", claude),
+    ]
+    .into_iter()
+    .filter(|(_, path)| path.exists())
+    .collect()
+}
+
+/// Apply the agent locations, the user's configured env, then the vars the
+/// shell owns. `host` is the LAN address to bind in addition to localhost, if any.
+fn apply_env(cmd: &mut Command, cfg: &AppConfig, port: u16, token: &str, host: Option<&str>) {
+    // Before the user's env, so a configured value can point elsewhere.
+    for (key, path) in agent_env() {
+        cmd.env(key, path);
+    }
+    for (key, var) in &cfg.env {
+        if RESERVED_ENV.contains(&key.as_str()) {
+            eprintln!("[server] Ignoring configured {key}: it is managed by the desktop app");
+            continue;
+        }
+        cmd.env(key, &var.value);
+    }
+    // Put the agent install dirs ahead of the configured or inherited PATH.
+    let base = match cfg.env.get("PATH") {
+        Some(var) => Some(OsString::from(&var.value)),
+        None => std::env::var_os("PATH"),
+    };
+    let mut dirs = vec![config::bin_dir(), config::node_bin_dir()];
+    dirs.extend(base.iter().flat_map(std::env::split_paths));
+    match std::env::join_paths(dirs) {
+        Ok(path) => {
+            cmd.env("PATH", path);
+        }
+        Err(e) => eprintln!("[server] Could not extend PATH: {e}"),
+    }
+    cmd.env("PORT", port.to_string())
+        .env(
+            "YEP_ANYWHERE_DATA_DIR",
+            config::data_dir().to_string_lossy().as_ref(),
+        )
+        .env("DESKTOP_AUTH_TOKEN", token);
+    if let Some(host) = host {
+        // Same as `--host` on the CLI: binds a network socket next to localhost.
+        cmd.env("HOST", host).env("CLI_HOST_OVERRIDE", "true");
+    }
+}
+
+/// Quote `arg` for a POSIX shell command line.
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
 /// Set up child process for clean shutdown: kill-on-drop and own process group.
+/// Output is piped so `logs::capture` can persist it.
 fn setup_child_process(cmd: &mut Command) {
-    cmd.kill_on_drop(true);
+    cmd.kill_on_drop(true)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
     #[cfg(unix)]
     {
         use std::os::unix::process::CommandExt;
@@ -90,120 +544,553 @@ fn setup_child_process(cmd: &mut Command) {
     }
 }
 
-#[tauri::command]
-pub async fn start_server(app: AppHandle) -> Result<(), String> {
-    let state = app.state::<ServerState>();
+/// Ask the OS for a free loopback port. The listener is dropped before the
+/// server binds, so another process can still grab it; `launch` retries then.
+fn pick_free_port() -> Result<u16, StartupError> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")
+        .map_err(|e| StartupError::spawn(format!("Failed to find free port: {e}")))?;
+    let addr = listener.local_addr().map_err(StartupError::spawn)?;
+    Ok(addr.port())
+}
 
-    {
-        let child_lock = state.child.lock().map_err(|e| e.to_string())?;
-        if child_lock.is_some() {
-            return Err("Server is already running".to_string());
+/// Spawn the server on `port` and store it in `ServerState`.
+/// Returns the generation of the new child.
+fn spawn_server(host: &Host, cfg: &AppConfig, port: u16) -> Result<u64, StartupError> {
+    let state = host.server();
+    let token = generate_token();
+    let bind_host = network::bind_host(&cfg.bind_address).map_err(StartupError::spawn)?;
+
+    let (mut child, command_marker, cwd) = if let Some(dev_dir) = config::dev_dir() {
+        // Dev mode: run the configured dev command from local source.
+        let dev = &cfg.dev;
+        let mut script = format!("exec {}", dev.command);
+        for arg in &cfg.extra_args {
+            script.push(' ');
+            script.push_str(&shell_quote(arg));
+        }
+        let cwd = dev.cwd(&dev_dir);
+        let mut cmd = Command::new(dev.shell());
+        if dev.login_shell {
+            cmd.arg("-l");
         }
+        cmd.args(["-c", &script]).current_dir(&cwd);
+        apply_env(&mut cmd, cfg, port, &token, bind_host.as_deref());
+        setup_child_process(&mut cmd);
+        let child = cmd.spawn().map_err(|e| {
+            StartupError::spawn(format!(
+                "Failed to start dev server in {}: {e}",
+                cwd.display()
+            ))
+        })?;
+        // The dev command alone could be any project's; the directory pins it down.
+        let cwd = cwd.canonicalize().unwrap_or(cwd);
+        (child, dev.command.clone(), Some(cwd))
+    } else {
+        // Production mode: run the installed npm package with the resolved runtime.
+        let runtime = runtime::resolve().map_err(|e| StartupError::NoRuntime { tried: e.tried })?;
+        let entry = server_entry().map_err(StartupError::spawn)?;
+        let mut cmd = runtime.script_command(&entry);
+        cmd.args(&cfg.extra_args).env("NODE_ENV", "production");
+        apply_env(&mut cmd, cfg, port, &token, bind_host.as_deref());
+        setup_child_process(&mut cmd);
+        let child = cmd
+            .spawn()
+            .map_err(|e| StartupError::spawn(format!("Failed to start server: {e}")))?;
+        (child, entry.to_string_lossy().to_string(), None)
+    };
+
+    state.bind_failed.store(false, Ordering::SeqCst);
+    if let Some(stdout) = child.stdout.take() {
+        logs::capture(host.clone(), stdout, logs::OutputStream::Stdout);
     }
+    if let Some(stderr) = child.stderr.take() {
+        logs::capture(host.clone(), stderr, logs::OutputStream::Stderr);
+    }
+
+    let pid = child.id();
+    if let Some(pid) = pid {
+        pidfile::write(&pidfile::PidFile {
+            pid,
+            port,
+            token_hash: pidfile::hash_token(&token),
+            command_marker,
+            cwd,
+            started_at: now_ms(),
+            owner_pid: std::process::id(),
+        });
+    }
+
+    // Held until the rest is filled in, like `clear_child_info`, so a shutdown
+    // can't clear them in between. The others are taken one at a time; output
+    // capture is already running and `record_listen` takes them too.
+    let mut child_lock = state.child.lock().map_err(StartupError::spawn)?;
+    *child_lock = Some(child);
+    *state.desktop_token.lock().map_err(StartupError::spawn)? = Some(token);
+    *state.port.lock().map_err(StartupError::spawn)? = Some(port);
+    *state.pid.lock().map_err(StartupError::spawn)? = pid;
+    *state.listen.lock().map_err(StartupError::spawn)? = ListenInfo {
+        requested_host: bind_host,
+        requested_port: Some(port),
+        local: None,
+        network: None,
+    };
+
+    Ok(state.next_generation())
+}
 
+/// Mark the server as crashed during startup and notify the frontend.
+fn startup_failed(host: &Host, err: StartupError) -> StartupError {
+    let (exit_code, signal) = match err {
+        StartupError::Exited { exit_code, signal } => (exit_code, signal),
+        _ => (None, None),
+    };
+    set_status(
+        host,
+        ServerStatus::Crashed {
+            exit_code,
+            signal,
+            at: now_ms(),
+        },
+    );
+    host.emit("server-startup-failed", &err);
+    err
+}
+
+/// Spawn the server and wait until `/health` answers, retrying on a fresh
+/// port if an auto-picked one turns out to be taken.
+/// Returns the generation of the new child for the supervisor to watch.
+async fn launch(host: &Host) -> Result<u64, StartupError> {
+    let state = host.server();
     let cfg = config::load_config();
-    let data_dir = config::data_dir();
-    let token = generate_token();
+    let timeout = Duration::from_secs(cfg.startup_timeout_secs);
 
-    // Resolve port: use user override or auto-pick a free port.
-    let port = match cfg.port {
-        Some(p) => p,
-        None => {
-            let listener = std::net::TcpListener::bind("127.0.0.1:0")
-                .map_err(|e| format!("Failed to find free port: {e}"))?;
-            let addr = listener.local_addr().map_err(|e| e.to_string())?;
-            addr.port()
-            // listener is dropped here, freeing the port for the server
+    if config::dev_dir().is_none() {
+        if let Err(e) = server_entry() {
+            set_status(host, ServerStatus::NeedsSetup);
+            return Err(StartupError::spawn(e));
         }
+    }
+    // Refuse to run a server whose auth and startup contract we don't match.
+    let compat = compat::check();
+    if !compat.compatible {
+        let supported = compat.supported.to_string();
+        set_status(
+            host,
+            ServerStatus::Incompatible {
+                installed: compat.installed.clone(),
+                supported: supported.clone(),
+                upgrade_available: compat.upgrade_available,
+            },
+        );
+        let err = StartupError::Incompatible {
+            installed: compat.installed,
+            supported,
+            upgrade_available: compat.upgrade_available,
+        };
+        host.emit("server-startup-failed", &err);
+        return Err(err);
+    }
+    set_status(host, ServerStatus::Starting);
+
+    // A user-specified port is used as-is; there is nothing to retry with.
+    let attempts = match cfg.port {
+        Some(_) => 1,
+        None => cfg.port_retries + 1,
     };
 
-    let child = if let Some(dev_dir) = config::dev_dir() {
-        // Dev mode: run `pnpm dev` from local source.
-        // Use a login shell so pnpm/node are on PATH (GUI apps have minimal PATH).
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
-        let mut cmd = Command::new(&shell);
-        cmd.args(["--login", "-c", "exec pnpm dev"])
-            .current_dir(&dev_dir)
-            .env("PORT", port.to_string())
-            .env("YEP_ANYWHERE_DATA_DIR", data_dir.to_string_lossy().as_ref())
-            .env("DESKTOP_AUTH_TOKEN", &token);
-        setup_child_process(&mut cmd);
-        cmd.spawn()
-            .map_err(|e| format!("Failed to start dev server in {}: {e}", dev_dir.display()))?
+    let mut attempt = 1;
+    loop {
+        let port = match cfg.port {
+            Some(p) => p,
+            None => pick_free_port().map_err(|e| startup_failed(host, e))?,
+        };
+        let generation = spawn_server(host, &cfg, port).map_err(|e| startup_failed(host, e))?;
+
+        // Only report success once the server actually answers; otherwise the
+        // webview races the child and loads a dead port.
+        let err = match wait_for_health(state, port, timeout).await {
+            Ok(()) => {
+                let pid = state.pid.lock().ok().and_then(|p| *p);
+                let port = state.port.lock().ok().and_then(|p| *p).unwrap_or(port);
+                set_status(
+                    host,
+                    ServerStatus::Ready {
+                        port,
+                        pid,
+                        since: now_ms(),
+                    },
+                );
+                host.emit(
+                    "server-port",
+                    ServerPort {
+                        port,
+                        attempts: attempt,
+                    },
+                );
+                tauri::async_runtime::spawn(health::watchdog(host.clone(), generation));
+                host.emit(
+                    "server-ready",
+                    ServerReady {
+                        port,
+                        version: server_version(),
+                    },
+                );
+                return Ok(generation);
+            }
+            // Whoever stopped or replaced the child owns the status now.
+            Err(_) if state.generation() != generation => return Err(StartupError::Cancelled),
+            Err(err) => err,
+        };
+
+        state.shutdown().await;
+        let port_problem = matches!(
+            err,
+            StartupError::AddrInUse { .. } | StartupError::Timeout { .. }
+        );
+        if port_problem && attempt < attempts {
+            eprintln!("[server] {err}; retrying on a new port ({attempt}/{attempts})");
+            attempt += 1;
+            continue;
+        }
+
+        return Err(startup_failed(host, err));
+    }
+}
+
+/// Called by `logs::capture` for every line of server output.
+pub(crate) fn observe_output(host: &Host, line: &str) {
+    if is_bind_error(line) {
+        host.server().bind_failed.store(true, Ordering::SeqCst);
+    }
+    if let Some((network, address)) = parse_listen_line(line) {
+        record_listen(host, network, address);
+    }
+}
+
+/// Whether `line` reports that the server's port is taken. Node prints
+/// "EADDRINUSE: address already in use", Bun "Is port 3400 in use?".
+fn is_bind_error(line: &str) -> bool {
+    let lower = line.to_ascii_lowercase();
+    if lower.contains("eaddrinuse") || lower.contains("address already in use") {
+        return true;
+    }
+    lower
+        .split_once("is port ")
+        .and_then(|(_, rest)| rest.split_once(" in use?"))
+        .is_some_and(|(port, _)| !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Parse the server's "listening" lines. Returns whether it is the LAN socket.
+fn parse_listen_line(line: &str) -> Option<(bool, ListenAddress)> {
+    let line = logs::strip_ansi(line);
+    // The maintenance server prints the same phrase for its own port.
+    if line.contains("[Maintenance]") {
+        return None;
+    }
+    if let Some((_, url)) = line.split_once("Server running at ") {
+        let url = reqwest::Url::parse(url.split_whitespace().next()?).ok()?;
+        let host = url.host_str()?.trim_matches(['[', ']']).to_string();
+        let port = url.port_or_known_default()?;
+        return Some((false, ListenAddress { host, port }));
+    }
+    if let Some((_, addr)) = line.split_once("Network socket listening on ") {
+        let (host, port) = addr.trim().rsplit_once(':')?;
+        return Some((
+            true,
+            ListenAddress {
+                host: host.trim_matches(['[', ']']).to_string(),
+                port: port.parse().ok()?,
+            },
+        ));
+    }
+    None
+}
+
+/// Record where the server says it is listening, adopt its real port, and
+/// warn if that isn't what it was asked for.
+fn record_listen(host: &Host, network: bool, actual: ListenAddress) {
+    let state = host.server();
+    let Ok(mut listen) = state.listen.lock() else {
+        return;
+    };
+    let expected_host = match network {
+        true => listen.requested_host.clone(),
+        false => Some("127.0.0.1".to_string()),
+    };
+    let matches = listen.requested_port == Some(actual.port)
+        && expected_host.as_deref() == Some(actual.host.as_str());
+    if network {
+        listen.network = Some(actual.clone());
     } else {
-        // Production mode: use bundled bun + installed npm package.
-        let bun = bun_path(&app)?;
-        let entry = server_entry()?;
-        let mut cmd = Command::new(&bun);
-        cmd.arg("run")
-            .arg(&entry)
-            .env("NODE_ENV", "production")
-            .env("PORT", port.to_string())
-            .env("YEP_ANYWHERE_DATA_DIR", data_dir.to_string_lossy().as_ref())
-            .env("DESKTOP_AUTH_TOKEN", &token);
-        setup_child_process(&mut cmd);
-        cmd.spawn()
-            .map_err(|e| format!("Failed to start server: {e}"))?
+        listen.local = Some(actual.clone());
+    }
+    let requested_port = listen.requested_port;
+    // Released first: `spawn_server` takes `port` and `listen` one at a time.
+    drop(listen);
+    if !network {
+        if let Ok(mut port) = state.port.lock() {
+            *port = Some(actual.port);
+        }
+    }
+    if matches {
+        return;
+    }
+
+    let mismatch = ListenMismatch {
+        requested_host: expected_host,
+        requested_port,
+        actual,
     };
+    eprintln!(
+        "[server] Listening on {}:{}, but asked for {}:{}",
+        mismatch.actual.host,
+        mismatch.actual.port,
+        mismatch.requested_host.as_deref().unwrap_or("?"),
+        mismatch
+            .requested_port
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| "?".to_string()),
+    );
+    host.emit("server-listen-mismatch", &mismatch);
+}
 
-    let mut child_lock = state.child.lock().map_err(|e| e.to_string())?;
-    *child_lock = Some(child);
+/// Wait for the child of `generation` to exit. Returns `None` if it was
+/// stopped or replaced deliberately, so there is nothing to restart.
+async fn wait_for_exit(state: &ServerState, generation: u64) -> Option<CrashRecord> {
+    loop {
+        tokio::time::sleep(SUPERVISOR_POLL_INTERVAL).await;
+        if state.generation() != generation {
+            return None;
+        }
+        let (status, pid) = {
+            let mut lock = state.child.lock().ok()?;
+            let status = match lock.as_mut()?.try_wait() {
+                Ok(Some(status)) => status,
+                _ => continue,
+            };
+            *lock = None;
+            (status, state.clear_child_info())
+        };
+        pidfile::remove();
+        // Agent subprocesses share the server's process group and outlive it.
+        if let Some(pid) = pid {
+            let drain = Duration::from_secs(config::load_config().shutdown_timeout_secs);
+            process::terminate_group(pid, drain).await;
+        }
+        let (exit_code, signal) = exit_parts(&status);
+        return Some(CrashRecord {
+            exit_code,
+            signal,
+            at: now_ms(),
+            message: format!("Server exited unexpectedly ({status})"),
+        });
+    }
+}
 
-    let mut token_lock = state.desktop_token.lock().map_err(|e| e.to_string())?;
-    *token_lock = Some(token);
+/// Watch the child of `generation` and restart it with exponential backoff when
+/// it crashes, until it is stopped deliberately or crashes too often.
+async fn supervise(host: Host, mut generation: u64, mut pending: Option<CrashRecord>) {
+    let state = host.server();
 
-    let mut port_lock = state.port.lock().map_err(|e| e.to_string())?;
-    *port_lock = Some(port);
+    loop {
+        let crash = match pending.take() {
+            Some(crash) => crash,
+            None => match wait_for_exit(state, generation).await {
+                Some(crash) => crash,
+                None => return,
+            },
+        };
 
-    Ok(())
+        let policy = config::load_config().restart;
+        let recent = state.record_crash(crash.clone(), policy.crash_window_secs);
+        let crash_loop = recent >= policy.max_crashes;
+        let restarting = policy.enabled && !crash_loop;
+        let restart_in_ms = restarting.then(|| backoff_ms(&policy, recent));
+
+        eprintln!("[server] {}", crash.message);
+        set_status(
+            &host,
+            ServerStatus::Crashed {
+                exit_code: crash.exit_code,
+                signal: crash.signal,
+                at: crash.at,
+            },
+        );
+        host.emit(
+            "server-crashed",
+            ServerCrashed {
+                crash: crash.clone(),
+                restarting,
+                restart_in_ms,
+                crash_loop,
+            },
+        );
+
+        let Some(delay) = restart_in_ms else {
+            if crash_loop {
+                eprintln!(
+                    "[server] {recent} crashes in the last {}s, giving up",
+                    policy.crash_window_secs
+                );
+            }
+            return;
+        };
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+
+        let _starting = state.lifecycle.lock().await;
+        // Stopped or restarted by the user while we were backing off.
+        if state.generation() != generation {
+            return;
+        }
+
+        match launch(&host).await {
+            Ok(next) => generation = next,
+            Err(StartupError::Cancelled) => return,
+            // Retrying won't change the installed version; leave the status for the UI.
+            Err(StartupError::Incompatible { .. }) => return,
+            Err(err) => {
+                pending = Some(CrashRecord::failed_launch(&err, "Restart failed"));
+                // `launch` stopped the half-started child, which bumped the generation.
+                generation = state.generation();
+            }
+        }
+    }
 }
 
-#[tauri::command]
-pub async fn stop_server(app: AppHandle) -> Result<(), String> {
-    let state = app.state::<ServerState>();
+/// Delay before the next restart: doubles per recent crash, capped at `max_backoff_ms`.
+fn backoff_ms(policy: &config::RestartPolicy, recent_crashes: u32) -> u64 {
+    let exponent = recent_crashes.saturating_sub(1).min(16);
+    policy
+        .initial_backoff_ms
+        .saturating_mul(1 << exponent)
+        .min(policy.max_backoff_ms)
+}
 
-    // Take the child out of the mutex so we don't hold the lock across .await
-    let child = {
-        let mut child_lock = state.child.lock().map_err(|e| e.to_string())?;
-        child_lock.take()
-    };
+/// Start the managed server (or begin watching an external one) and
+/// supervise it until it is stopped.
+pub async fn start(host: &Host) -> Result<(), String> {
+    start_with(host, false).await
+}
 
-    // Clear the desktop token and port
-    {
-        let mut token_lock = state.desktop_token.lock().map_err(|e| e.to_string())?;
-        *token_lock = None;
+/// Like `start`, but a first launch that fails the way a crash would is
+/// handed to the restart policy instead of returned. For `--headless` runs,
+/// where nobody is around to press Start again.
+pub async fn start_supervised(host: &Host) -> Result<(), String> {
+    start_with(host, true).await
+}
+
+async fn start_with(host: &Host, supervise_failed_launch: bool) -> Result<(), String> {
+    let state = host.server();
+
+    // Nothing to spawn for an external server; just (re)start watching its health.
+    if let ServerMode::External { url, .. } = config::load_config().server_mode {
+        let generation = state.next_generation();
+        set_status(host, ServerStatus::Starting);
+        tauri::async_runtime::spawn(health::monitor_external(host.clone(), url, generation));
+        return Ok(());
     }
+
+    // Claim the start before any await; a concurrent start waits here and
+    // then finds the child already running.
+    let _starting = state.lifecycle.lock().await;
     {
-        let mut port_lock = state.port.lock().map_err(|e| e.to_string())?;
-        *port_lock = None;
+        let child_lock = state.child.lock().map_err(|e| e.to_string())?;
+        if child_lock.is_some() {
+            return Err("Server is already running".to_string());
+        }
     }
 
-    if let Some(mut child) = child {
-        child.kill().await.map_err(|e| e.to_string())?;
-    }
+    // A server orphaned by a force-quit would hold the data dir (and maybe our port).
+    let drain = Duration::from_secs(config::load_config().shutdown_timeout_secs);
+    pidfile::reap_stale(drain).await?;
+
+    let (generation, pending) = match launch(host).await {
+        Ok(generation) => (generation, None),
+        Err(err) if supervise_failed_launch && err.is_crash() => {
+            let crash = CrashRecord::failed_launch(&err, "Start failed");
+            // `launch` stopped the half-started child, which bumped the generation.
+            (state.generation(), Some(crash))
+        }
+        Err(err) => return Err(err.to_string()),
+    };
+    tauri::async_runtime::spawn(supervise(host.clone(), generation, pending));
     Ok(())
 }
 
+/// Stop the server and its process group. Reports any processes that ignored
+/// SIGTERM and had to be force-killed.
+pub async fn stop(host: &Host) -> Result<ShutdownReport, String> {
+    // An external server isn't ours to stop.
+    if let ServerMode::External { .. } = config::load_config().server_mode {
+        return Ok(ShutdownReport::default());
+    }
+
+    set_status(host, ServerStatus::Stopping);
+    let report = host.server().shutdown().await;
+    set_status(host, ServerStatus::Stopped);
+    Ok(report)
+}
+
+/// Stop the server gracefully and start it again.
+pub async fn restart(host: &Host) -> Result<ShutdownReport, String> {
+    let report = stop(host).await?;
+    start(host).await?;
+    Ok(report)
+}
+
+/// Restart the server in the background, logging a failure under `reason`.
+/// For monitors that run inside the lifetime of the server they restart.
+pub(crate) fn spawn_restart(host: Host, reason: &'static str) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = restart(&host).await {
+            eprintln!("[server] {reason} restart failed: {e}");
+        }
+    });
+}
+
 #[tauri::command]
-pub async fn get_server_status(app: AppHandle) -> Result<String, String> {
+pub async fn start_server(app: AppHandle) -> Result<(), String> {
+    start(&Host::from(&app)).await
+}
+
+#[tauri::command]
+pub async fn stop_server(app: AppHandle) -> Result<ShutdownReport, String> {
+    stop(&Host::from(&app)).await
+}
+
+#[tauri::command]
+pub async fn restart_server(app: AppHandle) -> Result<ShutdownReport, String> {
+    restart(&Host::from(&app)).await
+}
+
+/// Where the server was asked to listen and where it reported listening.
+#[tauri::command]
+pub async fn get_server_listen(app: AppHandle) -> Result<ListenInfo, String> {
     let state = app.state::<ServerState>();
-    let mut child_lock = state.child.lock().map_err(|e| e.to_string())?;
-
-    match child_lock.as_mut() {
-        None => Ok("stopped".to_string()),
-        Some(child) => match child.try_wait() {
-            Ok(Some(_status)) => {
-                *child_lock = None;
-                Ok("stopped".to_string())
-            }
-            Ok(None) => Ok("running".to_string()),
-            Err(e) => Err(e.to_string()),
-        },
-    }
+    let listen = state.listen.lock().map_err(|e| e.to_string())?;
+    Ok(listen.clone())
+}
+
+#[tauri::command]
+pub async fn get_server_status(app: AppHandle) -> Result<ServerStatus, String> {
+    let state = app.state::<ServerState>();
+    let status = state.status.lock().map_err(|e| e.to_string())?;
+    Ok(status.clone())
+}
+
+/// Recent crashes, oldest first, so the UI can show why the server is down.
+#[tauri::command]
+pub async fn get_server_crashes(app: AppHandle) -> Result<Vec<CrashRecord>, String> {
+    let state = app.state::<ServerState>();
+    let crashes = state.crashes.lock().map_err(|e| e.to_string())?;
+    Ok(crashes.iter().cloned().collect())
 }
 
 #[tauri::command]
 pub async fn get_desktop_token(app: AppHandle) -> Result<Option<String>, String> {
+    if let ServerMode::External { token, .. } = config::load_config().server_mode {
+        return Ok(token);
+    }
     let state = app.state::<ServerState>();
     let token_lock = state.desktop_token.lock().map_err(|e| e.to_string())?;
     Ok(token_lock.clone())
@@ -211,7 +1098,22 @@ pub async fn get_desktop_token(app: AppHandle) -> Result<Option<String>, String>
 
 #[tauri::command]
 pub async fn get_server_port(app: AppHandle) -> Result<Option<u16>, String> {
+    if let ServerMode::External { url, .. } = config::load_config().server_mode {
+        return Ok(health::url_port(&url));
+    }
     let state = app.state::<ServerState>();
     let port_lock = state.port.lock().map_err(|e| e.to_string())?;
     Ok(*port_lock)
 }
+
+/// Base URL the webview should load: the external server's URL, or the
+/// managed server on localhost once it has a port.
+#[tauri::command]
+pub async fn get_server_url(app: AppHandle) -> Result<Option<String>, String> {
+    if let ServerMode::External { url, .. } = config::load_config().server_mode {
+        return Ok(Some(url.trim_end_matches('/').to_string()));
+    }
+    let state = app.state::<ServerState>();
+    let port_lock = state.port.lock().map_err(|e| e.to_string())?;
+    Ok(port_lock.map(|port| format!("http://localhost:{port}")))
+}
@@ -4,6 +4,8 @@ use tauri::{
     AppHandle, Manager,
 };
 
+const TRAY_ID: &str = "main";
+
 pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let open = MenuItem::with_id(app, "open", "Open Dashboard", true, None::<&str>)?;
     let restart = MenuItem::with_id(app, "restart", "Restart Server", true, None::<&str>)?;
@@ -11,7 +13,7 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
 
     let menu = Menu::with_items(app, &[&open, &restart, &quit])?;
 
-    let _tray = TrayIconBuilder::new()
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .icon(app.default_window_icon().unwrap().clone())
         .menu(&menu)
         .tooltip("Yep Anywhere")
@@ -25,8 +27,7 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
             "restart" => {
                 let app = app.clone();
                 tauri::async_runtime::spawn(async move {
-                    let _ = crate::server::stop_server(app.clone()).await;
-                    let _ = crate::server::start_server(app).await;
+                    let _ = crate::server::restart_server(app).await;
                 });
             }
             "quit" => {
@@ -56,3 +57,10 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Show the server's state in the tray tooltip, e.g. why it is down.
+pub fn set_status(app: &AppHandle, status: &str) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(format!("Yep Anywhere: {status}")));
+    }
+}
@@ -0,0 +1,195 @@
+use semver::{Version, VersionReq};
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::{self, ServerMode};
+use crate::server::{self, ServerState, ServerStatus};
+use crate::{compat, installer, manifest};
+
+const NPM_REGISTRY: &str = "https://registry.npmjs.org";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Installed versus available version of one component.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentUpdate {
+    /// `yep`, `claude` or `codex`, as used by the installer's progress events.
+    pub component: &'static str,
+    pub installed: Option<String>,
+    /// Newest version the configured version spec allows.
+    pub latest: Option<String>,
+    pub update_available: bool,
+    /// Why `installed` or `latest` couldn't be determined.
+    pub error: Option<String>,
+}
+
+pub(crate) fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent("yep-anywhere-desktop")
+        .build()
+        .unwrap_or_default()
+}
+
+/// Newest version of `package` that `spec` allows (a dist-tag, an exact
+/// version or a semver range), restricted to `within` if given.
+pub(crate) async fn npm_resolve(
+    client: &reqwest::Client,
+    package: &str,
+    spec: &str,
+    within: Option<&VersionReq>,
+) -> Result<String, String> {
+    // The abbreviated document has just dist-tags and versions.
+    let doc: serde_json::Value = client
+        .get(format!("{NPM_REGISTRY}/{package}"))
+        .header("Accept", "application/vnd.npm.install-v1+json")
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|e| format!("Failed to query npm for {package}: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse npm metadata for {package}: {e}"))?;
+
+    let pinned = match doc["dist-tags"][spec].as_str() {
+        Some(tagged) => Some(tagged.to_string()),
+        None => Version::parse(spec).is_ok().then(|| spec.to_string()),
+    };
+    if let Some(version) = pinned {
+        return match within {
+            Some(range) if !Version::parse(&version).is_ok_and(|v| range.matches(&v)) => Err(
+                format!("{package}@{spec} is {version}, outside the supported range {range}"),
+            ),
+            _ => Ok(version),
+        };
+    }
+
+    // npm separates comparators with spaces, the semver crate with commas.
+    let req = VersionReq::parse(&spec.split_whitespace().collect::<Vec<_>>().join(", "))
+        .map_err(|_| format!("Unsupported version spec {spec:?} for {package}"))?;
+    doc["versions"]
+        .as_object()
+        .into_iter()
+        .flat_map(|versions| versions.keys())
+        .filter_map(|v| Version::parse(v).ok())
+        .filter(|v| v.pre.is_empty() && req.matches(v))
+        .filter(|v| within.is_none_or(|range| range.matches(v)))
+        .max()
+        .map(|v| v.to_string())
+        .ok_or_else(|| match within {
+            Some(range) => {
+                format!("No published version of {package} matches {spec} within {range}")
+            }
+            None => format!("No published version of {package} matches {spec}"),
+        })
+}
+
+/// Version number in output like `codex-cli 0.20.0` or a tag like `rust-v0.20.0`.
+fn version_in(text: &str) -> Option<String> {
+    text.split(|c: char| c.is_whitespace() || c == '-')
+        .map(|part| part.trim_start_matches('v'))
+        .find(|part| Version::parse(part).is_ok())
+        .map(|part| part.to_string())
+}
+
+async fn codex_installed() -> Option<String> {
+    let codex = config::bin_dir().join(if cfg!(windows) { "codex.exe" } else { "codex" });
+    let output = tokio::process::Command::new(codex)
+        .arg("--version")
+        .output()
+        .await
+        .ok()?;
+    version_in(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn is_newer(latest: &str, installed: &str) -> bool {
+    match (Version::parse(latest), Version::parse(installed)) {
+        (Ok(latest), Ok(installed)) => latest > installed,
+        _ => latest != installed,
+    }
+}
+
+fn compare(
+    component: &'static str,
+    installed: Option<String>,
+    latest: Result<String, String>,
+) -> ComponentUpdate {
+    let (latest, error) = match latest {
+        Ok(latest) => (Some(latest), None),
+        Err(e) => (None, Some(e)),
+    };
+    let update_available = match (&installed, &latest) {
+        (Some(installed), Some(latest)) => is_newer(latest, installed),
+        _ => false,
+    };
+    ComponentUpdate {
+        component,
+        installed,
+        latest,
+        update_available,
+        error,
+    }
+}
+
+/// Compare installed versions of the server, Claude Code and Codex with the
+/// newest ones their version specs allow; for the server, only versions this
+/// app supports.
+#[tauri::command]
+pub async fn check_component_updates() -> Result<Vec<ComponentUpdate>, String> {
+    let client = client();
+    let server_spec = installer::server_spec();
+    let claude_spec = installer::claude_spec();
+    let supported = compat::supported_req();
+    let (server_latest, claude_latest, codex_release, codex_installed) = tokio::join!(
+        npm_resolve(&client, "yepanywhere", &server_spec, Some(&supported)),
+        npm_resolve(&client, "@anthropic-ai/claude-code", &claude_spec, None),
+        installer::latest_codex_release(&client),
+        codex_installed(),
+    );
+    let codex_latest = codex_release.and_then(|release| {
+        release["tag_name"]
+            .as_str()
+            .and_then(version_in)
+            .ok_or_else(|| "Latest Codex release has no version tag".to_string())
+    });
+
+    Ok(vec![
+        compare(
+            "yep",
+            manifest::installed_version("yepanywhere"),
+            server_latest,
+        ),
+        compare(
+            "claude",
+            manifest::installed_version("@anthropic-ai/claude-code"),
+            claude_latest,
+        ),
+        compare("codex", codex_installed, codex_latest),
+    ])
+}
+
+/// Reinstall `component` at the newest version its spec allows. Upgrading the
+/// server restarts it if it was running, so the new version takes over.
+#[tauri::command]
+pub async fn upgrade_component(app: AppHandle, component: String) -> Result<(), String> {
+    match component.as_str() {
+        "yep" => {
+            installer::install_yep_server(app.clone()).await?;
+            let managed = matches!(config::load_config().server_mode, ServerMode::Managed);
+            let running = matches!(
+                app.state::<ServerState>().status.lock().map(|s| s.clone()),
+                Ok(ServerStatus::Ready { .. }
+                    | ServerStatus::Starting
+                    | ServerStatus::Crashed { .. }
+                    | ServerStatus::Incompatible { .. })
+            );
+            if managed && running {
+                server::restart_server(app).await?;
+            }
+            Ok(())
+        }
+        "claude" => installer::install_claude(app).await,
+        "codex" => installer::install_codex(app).await,
+        _ => Err(format!("Unknown component: {component}")),
+    }
+}
@@ -1,80 +1,113 @@
 import { useEffect, useState } from "react";
 import {
   getDesktopToken,
-  getServerPort,
   getServerStatus,
+  getServerUrl,
+  installYepServer,
+  onServerStatusChanged,
+  type ServerStatus,
+  startServer,
 } from "../tauri";
 
 export function MainLayout() {
-  const [serverStatus, setServerStatus] = useState<string>("checking");
-  const [port, setPort] = useState<number | null>(null);
+  const [serverStatus, setServerStatus] = useState<ServerStatus | "error">();
+  const [upgrading, setUpgrading] = useState(false);
 
   useEffect(() => {
-    const check = () => {
-      getServerStatus()
-        .then(setServerStatus)
-        .catch(() => setServerStatus("error"));
+    let cancelled = false;
+    // An event is always newer than the initial read, so once one arrives
+    // the fetched status must not overwrite it.
+    let gotEvent = false;
+    const unlisten = onServerStatusChanged((status) => {
+      gotEvent = true;
+      setServerStatus(status);
+    });
+    // Register the listener before reading the status, so a transition in
+    // between isn't lost.
+    unlisten
+      .then(() => getServerStatus())
+      .then((status) => {
+        if (!cancelled && !gotEvent) setServerStatus(status);
+      })
+      .catch(() => {
+        if (!cancelled && !gotEvent) setServerStatus("error");
+      });
+    return () => {
+      cancelled = true;
+      unlisten.then((fn) => fn());
     };
-    check();
-    const interval = setInterval(check, 3000);
-    return () => clearInterval(interval);
   }, []);
 
-  // Fetch the active port from server state once it's running
+  // The shell only reports "ready" once /health answers, so navigate straight away
+  const ready = serverStatus !== "error" && serverStatus?.state === "ready";
   useEffect(() => {
-    if (serverStatus !== "running") {
-      setPort(null);
-      return;
-    }
-    if (port != null) return;
-
-    const poll = async () => {
-      for (let i = 0; i < 50; i++) {
-        const p = await getServerPort();
-        if (p != null) {
-          setPort(p);
-          return;
-        }
-        await new Promise((r) => setTimeout(r, 100));
-      }
-    };
-    poll();
-  }, [serverStatus, port]);
-
-  // Poll /health, then navigate webview to server URL
-  useEffect(() => {
-    if (serverStatus !== "running" || port == null) return;
+    if (!ready) return;
 
     let cancelled = false;
-    const poll = async () => {
-      // Wait for HTTP server to be ready
-      while (!cancelled) {
-        try {
-          const res = await fetch(`http://localhost:${port}/health`);
-          if (res.ok) break;
-        } catch {
-          // Server not ready yet
-        }
-        await new Promise((r) => setTimeout(r, 100));
-      }
-      if (cancelled) return;
-
+    const navigate = async () => {
+      const base = await getServerUrl();
+      if (cancelled || base == null) return;
       // Fetch desktop auth token and navigate
       try {
         const token = await getDesktopToken();
-        const url = token
-          ? `http://localhost:${port}/?desktop_token=${token}`
-          : `http://localhost:${port}`;
-        window.location.href = url;
+        if (cancelled) return;
+        window.location.href = token
+          ? `${base}/?desktop_token=${token}`
+          : base;
       } catch {
-        window.location.href = `http://localhost:${port}`;
+        window.location.href = base;
       }
     };
-    poll();
+    navigate();
     return () => {
       cancelled = true;
     };
-  }, [serverStatus, port]);
+  }, [ready]);
+
+  const upgrade = async () => {
+    setUpgrading(true);
+    try {
+      await installYepServer();
+      await startServer();
+    } catch {
+      setServerStatus("error");
+    } finally {
+      setUpgrading(false);
+    }
+  };
+
+  if (serverStatus !== "error" && serverStatus?.state === "incompatible") {
+    return (
+      <div
+        style={{
+          height: "100vh",
+          display: "flex",
+          flexDirection: "column",
+          gap: "1rem",
+          alignItems: "center",
+          justifyContent: "center",
+          color: "var(--text-secondary)",
+        }}
+      >
+        <div>
+          Yep Anywhere server {serverStatus.installed ?? "(unknown version)"}{" "}
+          is not supported by this app (requires {serverStatus.supported}).
+        </div>
+        {serverStatus.upgrade_available ? (
+          <button type="button" onClick={upgrade} disabled={upgrading}>
+            {upgrading ? "Upgrading..." : "Upgrade server"}
+          </button>
+        ) : (
+          <div>Update the desktop app to use this server version.</div>
+        )}
+      </div>
+    );
+  }
+
+  const failed =
+    serverStatus === "error" ||
+    serverStatus?.state === "crashed" ||
+    serverStatus?.state === "unreachable";
 
   return (
     <div
@@ -86,9 +119,11 @@ export function MainLayout() {
         color: "var(--text-secondary)",
       }}
     >
-      {serverStatus === "error"
+      {failed
         ? "Server error. Use tray menu to restart."
-        : "Starting server..."}
+        : serverStatus !== "error" && serverStatus?.state === "needs_setup"
+          ? "Yep Anywhere server not found. Run setup first."
+          : "Starting server..."}
     </div>
   );
 }
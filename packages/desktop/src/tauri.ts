@@ -1,12 +1,129 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 
+export type ServerMode =
+  | { kind: "managed" }
+  | {
+      kind: "external";
+      /** Base URL, e.g. http://workstation:3400 */
+      url: string;
+      /** The server's DESKTOP_AUTH_TOKEN, if it has one. */
+      token?: string | null;
+    };
+
+export type BindAddress =
+  | { kind: "loopback" }
+  | { kind: "all_interfaces" }
+  | { kind: "interface"; name: string };
+
 export interface AppConfig {
   setup_complete: boolean;
+  /** Spawn a local server (default) or attach to one running elsewhere. */
+  server_mode?: ServerMode;
   agents: string[];
   /** User-specified port override. Undefined/null = auto-pick a free port on each launch. */
   port?: number | null;
+  /** Retries on a fresh auto-picked port when the server fails to bind. */
+  port_retries?: number;
+  /** Which interfaces the server listens on besides localhost. */
+  bind_address?: BindAddress;
   start_minimized: boolean;
+  /** Seconds to wait for the server to answer /health after spawning it. */
+  startup_timeout_secs?: number;
+  restart?: RestartPolicy;
+  watchdog?: WatchdogPolicy;
+  logs?: LogRetention;
+  resources?: ResourcePolicy;
+  /** Seconds to wait after SIGTERM before force-killing the server's process group. */
+  shutdown_timeout_secs?: number;
+  /** Extra environment for the managed server (proxies, API keys, feature flags). */
+  env?: Record<string, EnvVar>;
+  /** Extra command-line arguments for the managed server. */
+  extra_args?: string[];
+  /** Which versions of the npm components the installer sets up. */
+  versions?: ComponentVersions;
+  /** Bun or Node binary to use when the bundled sidecar is missing. */
+  runtime_path?: string | null;
+  /** Refuse downloaded binaries whose release publishes no checksum. */
+  require_checksums?: boolean;
+  /** How the server is launched from source when YEP_DEV_DIR is set. */
+  dev?: DevLauncher;
+}
+
+/**
+ * npm version specs: exact ("1.2.3"), semver range ("^1.2") or dist-tag
+ * ("latest", "next"). Unset = the installer's default.
+ */
+export interface ComponentVersions {
+  /** yepanywhere server; defaults to, and is always kept within, the range this app supports. */
+  server?: string | null;
+  /** @anthropic-ai/claude-code; defaults to "latest". */
+  claude?: string | null;
+}
+
+export interface DevLauncher {
+  /** Shell command that starts the dev server, e.g. "pnpm dev". */
+  command: string;
+  /** Shell that runs the command. Undefined/null = $SHELL. */
+  shell?: string | null;
+  /** Run as a login shell so version managers put pnpm/node on PATH. */
+  login_shell: boolean;
+  /** Working directory, absolute or relative to the dev dir. */
+  cwd?: string | null;
+  watch: DevWatch;
+}
+
+export interface DevWatch {
+  /** Restart the dev server when files under paths change (applies on next launch). */
+  enabled: boolean;
+  /** Subpaths of the dev dir to watch recursively. */
+  paths: string[];
+  debounce_ms: number;
+}
+
+export interface EnvVar {
+  /** Secret values come back as REDACTED; send them back unchanged to keep them. */
+  value: string;
+  secret: boolean;
+}
+
+/** Placeholder returned by getConfig() in place of secret env values. */
+export const REDACTED = "********";
+
+export interface RestartPolicy {
+  enabled: boolean;
+  /** Give up once this many crashes happen within crash_window_secs. */
+  max_crashes: number;
+  crash_window_secs: number;
+  initial_backoff_ms: number;
+  max_backoff_ms: number;
+}
+
+export interface WatchdogPolicy {
+  enabled: boolean;
+  /** How often to poll /health once the server is ready. */
+  interval_secs: number;
+  /** How long one poll may take before it counts as a failure. */
+  timeout_secs: number;
+  /** Restart after this many consecutive failed polls. */
+  max_failures: number;
+}
+
+export interface LogRetention {
+  /** Rotate the active log file once it would grow past this size. */
+  max_file_bytes: number;
+  /** Number of rotated files to keep next to the active one. */
+  max_files: number;
+  max_age_days: number;
+}
+
+export interface ResourcePolicy {
+  /** How often to sample the server's CPU and memory. */
+  sample_interval_secs: number;
+  /** Restart the server gracefully above this much resident memory (MiB). */
+  memory_limit_mb?: number | null;
+  /** Consecutive samples over the limit before restarting. */
+  memory_limit_samples: number;
 }
 
 export async function getConfig(): Promise<AppConfig> {
@@ -30,14 +147,56 @@ export async function startServer(): Promise<void> {
   return invoke("start_server");
 }
 
-export async function stopServer(): Promise<void> {
+export interface ShutdownReport {
+  /** PID of the server child, if one was running. */
+  pid: number | null;
+  /** True if everything exited within the drain timeout after SIGTERM. */
+  graceful: boolean;
+  /** Processes that ignored SIGTERM and had to be force-killed. */
+  forced_pids: number[];
+}
+
+export async function stopServer(): Promise<ShutdownReport> {
   return invoke("stop_server");
 }
 
-export async function getServerStatus(): Promise<string> {
+/** Stop the server gracefully and start it again. */
+export async function restartServer(): Promise<ShutdownReport> {
+  return invoke("restart_server");
+}
+
+export type ServerStatus =
+  | { state: "stopped" }
+  | { state: "starting" }
+  | { state: "ready"; port: number; pid: number | null; since: number }
+  | { state: "stopping" }
+  | {
+      state: "crashed";
+      exit_code: number | null;
+      signal: number | null;
+      at: number;
+    }
+  | { state: "needs_setup" }
+  | {
+      state: "incompatible";
+      installed: string | null;
+      supported: string;
+      upgrade_available: boolean;
+    }
+  | { state: "unreachable"; url: string; since: number };
+
+export async function getServerStatus(): Promise<ServerStatus> {
   return invoke("get_server_status");
 }
 
+export function onServerStatusChanged(
+  callback: (status: ServerStatus) => void,
+) {
+  return listen<ServerStatus>("server-status-changed", (event) =>
+    callback(event.payload),
+  );
+}
+
 export async function getDesktopToken(): Promise<string | null> {
   return invoke("get_desktop_token");
 }
@@ -46,6 +205,195 @@ export async function getServerPort(): Promise<number | null> {
   return invoke("get_server_port");
 }
 
+export interface CrashRecord {
+  exit_code: number | null;
+  signal: number | null;
+  /** Unix timestamp in milliseconds. */
+  at: number;
+  message: string;
+}
+
+export async function getServerCrashes(): Promise<CrashRecord[]> {
+  return invoke("get_server_crashes");
+}
+
+export interface ProcessUsage {
+  pid: number;
+  rss_bytes: number;
+  /** Cumulative CPU time in milliseconds. */
+  cpu_time_ms: number;
+}
+
+export interface ServerResources {
+  pid: number;
+  /** Resident memory summed over the server's process group. */
+  rss_bytes: number;
+  /** CPU use since the previous sample (100 = one core); null for the first sample. */
+  cpu_percent: number | null;
+  processes: ProcessUsage[];
+  /** Unix timestamp in milliseconds. */
+  sampled_at: number;
+}
+
+/** Current CPU and memory of the managed server, or null if it isn't running. */
+export async function getServerResources(): Promise<ServerResources | null> {
+  return invoke("get_server_resources");
+}
+
+export function onServerResources(
+  callback: (resources: ServerResources) => void,
+) {
+  return listen<ServerResources>("server-resources", (event) =>
+    callback(event.payload),
+  );
+}
+
+export interface MemoryLimitExceeded {
+  rss_bytes: number;
+  limit_bytes: number;
+}
+
+/** Fired just before the server is restarted for exceeding its memory limit. */
+export function onServerMemoryLimit(
+  callback: (event: MemoryLimitExceeded) => void,
+) {
+  return listen<MemoryLimitExceeded>("server-memory-limit", (event) =>
+    callback(event.payload),
+  );
+}
+
+export interface LogChunk {
+  path: string;
+  text: string;
+  /** Byte offset of text within the file. */
+  start: number;
+  /** Byte offset just past text; pass as offset to continue reading. */
+  end: number;
+  size: number;
+}
+
+/**
+ * Read captured server output: the last `lines` lines by default, or
+ * `length` bytes starting at `offset` when an offset is given.
+ */
+export async function getServerLogs(options: {
+  lines?: number;
+  offset?: number;
+  length?: number;
+} = {}): Promise<LogChunk> {
+  return invoke("get_server_logs", options);
+}
+
+export type LogLevel = "trace" | "debug" | "info" | "warn" | "error" | "fatal";
+
+export interface ServerLogLine {
+  level: LogLevel;
+  component: string | null;
+  message: string;
+  /** Unix timestamp in milliseconds. */
+  timestamp: number;
+  stream: "stdout" | "stderr";
+}
+
+/** Start (or re-filter) the live server-log event stream. Defaults to info. */
+export async function subscribeServerLogs(minLevel?: LogLevel): Promise<void> {
+  return invoke("subscribe_server_logs", { minLevel });
+}
+
+export async function unsubscribeServerLogs(): Promise<void> {
+  return invoke("unsubscribe_server_logs");
+}
+
+export function onServerLog(callback: (line: ServerLogLine) => void) {
+  return listen<ServerLogLine>("server-log", (event) =>
+    callback(event.payload),
+  );
+}
+
+/** Base URL the webview should load, or null while the server has no port. */
+export async function getServerUrl(): Promise<string | null> {
+  return invoke("get_server_url");
+}
+
+export interface ListenAddress {
+  host: string;
+  port: number;
+}
+
+export interface ListenInfo {
+  requested_host: string | null;
+  requested_port: number | null;
+  /** The loopback socket the server reported. */
+  local: ListenAddress | null;
+  /** The LAN socket, when a bind address is configured. */
+  network: ListenAddress | null;
+}
+
+/** Where the server was asked to listen and where it reported listening. */
+export async function getServerListen(): Promise<ListenInfo> {
+  return invoke("get_server_listen");
+}
+
+export interface ListenMismatch {
+  requested_host: string | null;
+  requested_port: number | null;
+  actual: ListenAddress;
+}
+
+/** Fired when the server listens somewhere other than where it was asked to. */
+export function onServerListenMismatch(
+  callback: (mismatch: ListenMismatch) => void,
+) {
+  return listen<ListenMismatch>("server-listen-mismatch", (event) =>
+    callback(event.payload),
+  );
+}
+
+export interface NetworkAddress {
+  interface: string;
+  ip: string;
+  family: "ipv4" | "ipv6";
+  /** Set for addresses the running server is reachable on. */
+  url: string | null;
+}
+
+/** This machine's non-loopback addresses, for showing LAN URLs. */
+export async function listNetworkAddresses(): Promise<NetworkAddress[]> {
+  return invoke("list_network_addresses");
+}
+
+export interface ServerCompatibility {
+  /** Installed server version, if its package.json could be read. */
+  installed: string | null;
+  /** Semver range this app supports. */
+  supported: string;
+  compatible: boolean;
+  /** True if installing a supported version would fix it (installed is too old). */
+  upgrade_available: boolean;
+}
+
+export async function checkServerCompatibility(): Promise<ServerCompatibility> {
+  return invoke("check_server_compatibility");
+}
+
+export interface Runtime {
+  kind: "bun" | "node";
+  path: string;
+  version: string;
+  source: "sidecar" | "configured" | "path";
+}
+
+/** A runtime location that was considered and rejected, and why. */
+export interface TriedLocation {
+  location: string;
+  reason: string;
+}
+
+/** The JS runtime the app uses: bundled sidecar, runtime_path, then bun/node on PATH. */
+export async function getRuntime(): Promise<Runtime> {
+  return invoke("get_runtime");
+}
+
 export async function installYepServer(): Promise<void> {
   return invoke("install_yep_server");
 }
@@ -66,6 +414,47 @@ export async function checkClaudeAuth(): Promise<boolean> {
   return invoke("check_claude_auth");
 }
 
+export interface ComponentRecord {
+  /** The requested version spec. */
+  spec: string;
+  /** What it resolved to. */
+  version: string;
+  /** Unix timestamp in milliseconds. */
+  installed_at: number;
+}
+
+export interface ComponentManifest {
+  /** Keyed by package name, or "codex". */
+  components: Record<string, ComponentRecord>;
+}
+
+/** Resolved versions of the installed components, for reproducing a setup. */
+export async function getComponentManifest(): Promise<ComponentManifest> {
+  return invoke("get_component_manifest");
+}
+
+export type Component = "yep" | "claude" | "codex";
+
+export interface ComponentUpdate {
+  component: Component;
+  installed: string | null;
+  /** Newest version the configured version spec allows. */
+  latest: string | null;
+  update_available: boolean;
+  /** Why installed or latest couldn't be determined. */
+  error: string | null;
+}
+
+/** Compare installed server, Claude Code and Codex versions with the latest. */
+export async function checkComponentUpdates(): Promise<ComponentUpdate[]> {
+  return invoke("check_component_updates");
+}
+
+/** Reinstall a component at its latest allowed version; restarts the server for "yep". */
+export async function upgradeComponent(component: Component): Promise<void> {
+  return invoke("upgrade_component", { component });
+}
+
 export async function spawnPty(
   command: string,
   args: string[],
@@ -85,10 +474,49 @@ export async function killPty(): Promise<void> {
   return invoke("kill_pty");
 }
 
+/** Why a package install failed. */
+export interface InstallError {
+  package: string;
+  spec: string;
+  /** null if the installer was killed by a signal. */
+  exit_code: number | null;
+  /** The most relevant line of output. */
+  summary: string;
+  /** The last lines of stderr. */
+  stderr_tail: string[];
+}
+
 export interface InstallProgress {
   agent: string;
+  /** "installing", "log", "done", "error", or a Codex download step. */
   status: string;
   message: string;
+  phase: "resolving" | "downloading" | "linking" | null;
+  /** Milliseconds since the install started. */
+  elapsed_ms: number | null;
+  /** Which output stream a "log" line came from. */
+  stream: "stdout" | "stderr" | null;
+  /** Set on the final "error" event of a package install. */
+  error: InstallError | null;
+  /** Set on "downloading" events of a binary download. */
+  download: DownloadProgress | null;
+  /** Set on the "error" event when a download fails verification. */
+  integrity: IntegrityError | null;
+}
+
+/** A downloaded binary that couldn't be verified against its release. */
+export type IntegrityError =
+  | { kind: "mismatch"; asset: string; expected: string; actual: string }
+  | { kind: "missing_checksum"; asset: string };
+
+export interface DownloadProgress {
+  received_bytes: number;
+  /** From Content-Length; null if the server didn't send one. */
+  total_bytes: number | null;
+  /** Average since the download started. */
+  bytes_per_sec: number;
+  /** null until the total and a rate are known. */
+  eta_secs: number | null;
 }
 
 export function onInstallProgress(
@@ -99,6 +527,77 @@ export function onInstallProgress(
   );
 }
 
+export interface ServerReady {
+  port: number;
+  version: string | null;
+}
+
+export function onServerReady(callback: (ready: ServerReady) => void) {
+  return listen<ServerReady>("server-ready", (event) =>
+    callback(event.payload),
+  );
+}
+
+export interface ServerPort {
+  port: number;
+  /** How many spawn attempts it took. */
+  attempts: number;
+}
+
+export function onServerPort(callback: (port: ServerPort) => void) {
+  return listen<ServerPort>("server-port", (event) => callback(event.payload));
+}
+
+export type StartupError =
+  | { kind: "spawn"; message: string }
+  | { kind: "exited"; exit_code: number | null; signal: number | null }
+  | { kind: "timeout"; secs: number }
+  | { kind: "addr_in_use"; port: number }
+  | {
+      kind: "incompatible";
+      installed: string | null;
+      supported: string;
+      upgrade_available: boolean;
+    }
+  | { kind: "no_runtime"; tried: TriedLocation[] }
+  | { kind: "cancelled" };
+
+export function onServerStartupFailed(
+  callback: (error: StartupError) => void,
+) {
+  return listen<StartupError>("server-startup-failed", (event) =>
+    callback(event.payload),
+  );
+}
+
+export interface ServerCrashed {
+  crash: CrashRecord;
+  restarting: boolean;
+  restart_in_ms: number | null;
+  /** True when the supervisor gave up because the server keeps crashing. */
+  crash_loop: boolean;
+}
+
+export function onServerCrashed(callback: (event: ServerCrashed) => void) {
+  return listen<ServerCrashed>("server-crashed", (event) =>
+    callback(event.payload),
+  );
+}
+
+export interface ServerUnresponsive {
+  failures: number;
+  timeout_secs: number;
+}
+
+/** Fired when the watchdog restarts a server that stopped answering /health. */
+export function onServerUnresponsive(
+  callback: (event: ServerUnresponsive) => void,
+) {
+  return listen<ServerUnresponsive>("server-unresponsive", (event) =>
+    callback(event.payload),
+  );
+}
+
 export function onPtyOutput(callback: (data: string) => void) {
   return listen<{ data: string }>("pty-output", (event) =>
     callback(event.payload.data),
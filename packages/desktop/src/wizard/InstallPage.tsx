@@ -42,8 +42,13 @@ export function InstallPage({ agents, onNext }: Props) {
           t.id === progress.agent
             ? {
                 ...t,
-                status: progress.status as TaskStatus["status"],
-                message: progress.message,
+                // Output lines and download/extract steps keep the task
+                // installing; show the latest message.
+                status:
+                  progress.status === "done" || progress.status === "error"
+                    ? progress.status
+                    : "installing",
+                message: progress.error?.summary ?? progress.message,
               }
             : t,
         ),
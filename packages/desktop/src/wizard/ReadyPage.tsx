@@ -1,6 +1,6 @@
 import { useState } from "react";
 import { enable as enableAutostart } from "@tauri-apps/plugin-autostart";
-import { saveConfig, startServer, type AppConfig } from "../tauri";
+import { getConfig, saveConfig, startServer, type AppConfig } from "../tauri";
 
 interface Props {
   agents: string[];
@@ -22,13 +22,15 @@ export function ReadyPage({
     setLaunching(true);
     setError(null);
 
-    const config: AppConfig = {
-      setup_complete: true,
-      agents,
-      start_minimized: startMinimized,
-    };
-
     try {
+      // Keep anything already in config.json (env, versions, server mode...);
+      // redacted secrets are restored on save.
+      const config: AppConfig = {
+        ...(await getConfig()),
+        setup_complete: true,
+        agents,
+        start_minimized: startMinimized,
+      };
       await saveConfig(config);
       if (autostart) {
         await enableAutostart();
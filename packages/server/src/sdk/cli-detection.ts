@@ -39,10 +39,24 @@ export interface ClaudeCliInfo {
  * @returns Information about the CLI installation
  */
 export function detectClaudeCli(): ClaudeCliInfo {
+  const desktopPath = getDesktopClaudePath();
+  if (desktopPath) {
+    return { found: true, path: desktopPath, version: "(desktop-installed)" };
+  }
   // Short-circuit: let the SDK handle CLI spawning and errors
   return { found: true, path: "claude", version: "(SDK-managed)" };
 }
 
+/**
+ * Claude Code entry script installed by the desktop app
+ * (YEP_ANYWHERE_CLAUDE_PATH), if set and present. Passed to the SDK as
+ * pathToClaudeCodeExecutable so local sessions run that install.
+ */
+export function getDesktopClaudePath(): string | undefined {
+  const desktopPath = process.env.YEP_ANYWHERE_CLAUDE_PATH;
+  return desktopPath && existsSync(desktopPath) ? desktopPath : undefined;
+}
+
 /**
  * Information about the Codex CLI installation.
  */
@@ -106,10 +120,14 @@ export function getCodexCommonPaths(): string[] {
 }
 
 /**
- * Find the Codex CLI path by checking PATH first, then common locations.
+ * Find the Codex CLI path: the desktop app's install (YEP_ANYWHERE_CODEX_PATH)
+ * first, then PATH, then common locations.
  * Returns the path if found, null otherwise.
  */
 export async function findCodexCliPath(): Promise<string | null> {
+  const desktopPath = process.env.YEP_ANYWHERE_CODEX_PATH;
+  if (desktopPath && existsSync(desktopPath)) return desktopPath;
+
   try {
     const { stdout } = await execAsync(whichCommand("codex"), {
       encoding: "utf-8",
@@ -14,7 +14,7 @@ import {
   getModelContextWindow,
 } from "@yep-anywhere/shared";
 import { getLogger } from "../../logging/logger.js";
-import { detectClaudeCli } from "../cli-detection.js";
+import { detectClaudeCli, getDesktopClaudePath } from "../cli-detection.js";
 import { logSDKMessage } from "../messageLogger.js";
 import { MessageQueue } from "../messageQueue.js";
 import {
@@ -277,6 +277,7 @@ export class ClaudeProvider implements AgentProvider {
           permissionMode: "default",
           persistSession: false,
           env: this.getEnv(),
+          pathToClaudeCodeExecutable: getDesktopClaudePath(),
         },
       });
 
@@ -478,6 +479,10 @@ export class ClaudeProvider implements AgentProvider {
           effort: options.effort,
           // Filter env to exclude npm_*, yep-anywhere specific, and other irrelevant vars
           env: this.getEnv(),
+          // The desktop app's install is a local path; remote hosts use their own.
+          pathToClaudeCodeExecutable: options.executor
+            ? undefined
+            : getDesktopClaudePath(),
           // Remote execution via SSH
           spawnClaudeCodeProcess,
         },